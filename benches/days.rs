@@ -0,0 +1,57 @@
+use aoc2020::{day15, day9, registry, Day};
+use criterion::measurement::WallTime;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, Criterion};
+use std::fs;
+use std::time::Duration;
+
+/// The checked-in puzzle input for `day`.
+fn input(day: u8) -> String {
+    let path = format!("{}/input/2020/day{}.txt", env!("CARGO_MANIFEST_DIR"), day);
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {}: {}", path, err))
+}
+
+/// Benchmarks parsing and each part separately, so a regression in one phase is not
+/// hidden by the other.
+fn bench_day(group: &mut BenchmarkGroup<WallTime>, day: &Day, input: &str) {
+    group.bench_function("parse", |b| b.iter(|| day.parse(black_box(input)).unwrap()));
+
+    let parsed = day.parse(input).unwrap();
+    for part in 1..=2 {
+        group.bench_function(format!("part{}", part), |b| {
+            b.iter(|| day.solve_parsed(black_box(parsed.as_ref()), part).unwrap())
+        });
+    }
+}
+
+fn days(c: &mut Criterion) {
+    for day in registry::days() {
+        let input = input(day.day());
+        let mut group = c.benchmark_group(format!("day{}", day.day()));
+        if day.day() == 15 {
+            // Part 2 plays 30,000,000 turns, which takes seconds per iteration.
+            group.sample_size(10);
+            group.measurement_time(Duration::from_secs(60));
+        }
+        bench_day(&mut group, day, &input);
+        group.finish();
+    }
+}
+
+/// The slow routines on their own, away from the generic per-day harness.
+fn hot_spots(c: &mut Criterion) {
+    let numbers = day9::input_generator(&input(9)).unwrap();
+    c.bench_function("day9/get_encryption_weakness", |b| {
+        b.iter(|| day9::get_encryption_weakness(black_box(&numbers), 25))
+    });
+
+    let starting = day15::input_generator(&input(15)).unwrap();
+    c.bench_function("day15/run/2020", |b| {
+        b.iter(|| day15::run(black_box(&starting), 2020))
+    });
+    c.bench_function("day15/run/300000", |b| {
+        b.iter(|| day15::run(black_box(&starting), 300_000))
+    });
+}
+
+criterion_group!(benches, days, hot_spots);
+criterion_main!(benches);
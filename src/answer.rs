@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// The result of solving one part of a day, whatever integer type the solver uses.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Signed(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(Unsigned, u64, u16, u32, u64, usize);
+impl_from!(Signed, i64, i32, i64, isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        assert_eq!(Answer::from(42u16).to_string(), "42");
+        assert_eq!(Answer::from(-7isize).to_string(), "-7");
+        assert_eq!(Answer::from(u64::MAX), Answer::Unsigned(u64::MAX));
+    }
+}
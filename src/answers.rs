@@ -0,0 +1,113 @@
+use crate::input::normalize;
+use crate::Answer;
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{fs, io};
+
+/// A known-correct answer for one part of a day, tied to the input it was computed from.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+}
+
+/// Known-correct answers, stored as TOML so they can be checked into the repository.
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AnswerStore {
+    #[serde(default, rename = "answer")]
+    records: Vec<Record>,
+}
+
+impl AnswerStore {
+    /// Loads a store from `path`, or an empty store if the file doesn't exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(AnswerStore::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents =
+            toml::to_string(self).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, contents)
+    }
+
+    /// The recorded answer for `part` of `day` on the input with hash `input`.
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<&str> {
+        self.records
+            .iter()
+            .find(|r| r.day == day && r.part == part && r.input == input)
+            .map(|r| r.answer.as_str())
+    }
+
+    /// Records `answer`, replacing any previous answer for the same day, part and input.
+    pub fn record(&mut self, day: u8, part: u8, input: &str, answer: &Answer) {
+        let answer = answer.to_string();
+        match self
+            .records
+            .iter_mut()
+            .find(|r| r.day == day && r.part == part && r.input == input)
+        {
+            Some(record) => record.answer = answer,
+            None => {
+                self.records.push(Record {
+                    day,
+                    part,
+                    input: input.to_string(),
+                    answer,
+                });
+                self.records.sort_by_key(|r| (r.day, r.part));
+            }
+        }
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+}
+
+/// Hex SHA-256 of a normalized puzzle input, used to tell different inputs for the
+/// same day apart regardless of line endings or trailing whitespace.
+pub fn input_hash(input: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input_str(&normalize(input));
+    hasher.result_str()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_test() {
+        let hash = input_hash("0,3,6");
+        let mut store = AnswerStore::default();
+        store.record(15, 2, &hash, &Answer::Unsigned(175594));
+        store.record(15, 1, &hash, &Answer::Unsigned(0));
+        store.record(15, 1, &hash, &Answer::Unsigned(436));
+
+        assert_eq!(store.records().len(), 2);
+        assert_eq!(store.records()[0].part, 1);
+        assert_eq!(store.get(15, 1, &hash), Some("436"));
+        assert_eq!(store.get(15, 1, &input_hash("1,3,2")), None);
+
+        let toml = toml::to_string(&store).unwrap();
+        assert_eq!(toml::from_str::<AnswerStore>(&toml).unwrap(), store);
+    }
+
+    #[test]
+    fn input_hash_test() {
+        assert_eq!(
+            input_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(input_hash("0,3,6\r\n"), input_hash("0,3,6"));
+    }
+}
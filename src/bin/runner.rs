@@ -0,0 +1,528 @@
+use aoc2020::answers::{input_hash, AnswerStore};
+use aoc2020::{registry, Answer, Day, Error};
+use serde::Serialize;
+use std::io::Read;
+use std::time::{Duration, Instant};
+use std::{env, fs, io, process};
+
+const USAGE: &str = "\
+Usage: runner --day <N> [--part <1|2>] [--input <FILE|->] [--json] [--record | --verify]
+       runner --all [--part <1|2>] [--json] [--record | --verify]
+
+Options:
+    --day <N>       Day to solve
+    --part <P>      Only solve part P (default: both)
+    --input <FILE>  Read puzzle input from FILE, or stdin for `-`
+                    (default: input/2020/day<N>.txt)
+    --all           Solve every implemented day with its default input
+    --json          Print one JSON object per day and part
+    --record        Save the answers as known-correct for their inputs
+    --verify        Compare the answers against the recorded ones, failing if
+                    any differs or was never recorded
+    --answers <FILE>
+                    File holding recorded answers (default: answers.toml)
+    --help          Print this message";
+
+const DEFAULT_ANSWERS: &str = "answers.toml";
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+enum Mode {
+    #[default]
+    Solve,
+    Record,
+    Verify,
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<String>,
+    all: bool,
+    json: bool,
+    mode: Mode,
+    answers: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", name))
+        };
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number(&value("--day")?, "day")?),
+            "--part" => match parse_number(&value("--part")?, "part")? {
+                part @ 1..=2 => options.part = Some(part),
+                part => return Err(format!("part {} does not exist", part)),
+            },
+            "--input" => options.input = Some(value("--input")?),
+            "--all" => options.all = true,
+            "--json" => options.json = true,
+            "--record" | "--verify" if options.mode != Mode::Solve => {
+                return Err("--record cannot be combined with --verify".to_string());
+            }
+            "--record" => options.mode = Mode::Record,
+            "--verify" => options.mode = Mode::Verify,
+            "--answers" => options.answers = Some(value("--answers")?),
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+    match (options.all, options.day, &options.input) {
+        (true, Some(_), _) => Err("--all cannot be combined with --day".to_string()),
+        (true, _, Some(_)) => Err("--all cannot be combined with --input".to_string()),
+        (false, None, _) => Err("either --day or --all is required".to_string()),
+        _ if options.json && options.mode == Mode::Verify => {
+            Err("--json cannot be combined with --verify".to_string())
+        }
+        _ => Ok(options),
+    }
+}
+
+fn parse_number(value: &str, what: &str) -> Result<u8, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {} `{}`", what, value))
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    let input = match path {
+        "-" => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map(|_| input)
+        }
+        _ => fs::read_to_string(path),
+    };
+    input.map_err(|err| format!("cannot read {}: {}", path, err))
+}
+
+fn default_input(day: u8) -> String {
+    format!("input/2020/day{}.txt", day)
+}
+
+#[derive(Debug)]
+enum Failure {
+    Input(String),
+    Solve(Error),
+}
+
+/// What happened when solving one part of one day. Failures to read or parse the
+/// input apply to the whole day and have no part.
+#[derive(Debug)]
+struct Outcome {
+    day: u8,
+    part: Option<u8>,
+    input_hash: Option<String>,
+    parse_time: Option<Duration>,
+    solve_time: Option<Duration>,
+    answer: Result<Answer, Failure>,
+}
+
+/// Solves the requested parts of `day`, timing the parse and each part separately.
+fn run_day(day: &Day, parts: &[u8], input: Result<String, String>) -> Vec<Outcome> {
+    let input = match input {
+        Ok(input) => input,
+        Err(err) => {
+            return vec![Outcome {
+                day: day.day(),
+                part: None,
+                input_hash: None,
+                parse_time: None,
+                solve_time: None,
+                answer: Err(Failure::Input(err)),
+            }]
+        }
+    };
+    let hash = input_hash(&input);
+    let outcome = |part, parse_time, solve_time, answer| Outcome {
+        day: day.day(),
+        part,
+        input_hash: Some(hash.clone()),
+        parse_time,
+        solve_time,
+        answer,
+    };
+
+    let start = Instant::now();
+    let parsed = day.parse(&input);
+    let parse_time = Some(start.elapsed());
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let err = Failure::Solve(Error::from(err));
+            return vec![outcome(None, parse_time, None, Err(err))];
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = day.solve_parsed(parsed.as_ref(), part);
+            let solve_time = Some(start.elapsed());
+            outcome(
+                Some(part),
+                parse_time,
+                solve_time,
+                answer.map_err(Failure::Solve),
+            )
+        })
+        .collect()
+}
+
+fn print_text(outcome: &Outcome) {
+    match &outcome.answer {
+        Ok(answer) => println!(
+            "Day {} - part {}: {} (parse {:?}, solve {:?})",
+            outcome.day,
+            outcome.part.unwrap_or_default(),
+            answer,
+            outcome.parse_time.unwrap_or_default(),
+            outcome.solve_time.unwrap_or_default()
+        ),
+        Err(Failure::Input(err)) => eprintln!("error: {}", err),
+        Err(Failure::Solve(err)) => eprintln!("error: {}", err),
+    }
+}
+
+#[derive(Serialize)]
+struct JsonOutcome {
+    day: u8,
+    part: Option<u8>,
+    answer: Option<String>,
+    parse_time_ns: Option<u128>,
+    solve_time_ns: Option<u128>,
+    error: Option<JsonError>,
+}
+
+#[derive(Serialize)]
+struct JsonError {
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+impl JsonError {
+    fn new(kind: &'static str, message: String) -> Self {
+        JsonError {
+            kind,
+            message,
+            line: None,
+            column: None,
+            text: None,
+        }
+    }
+}
+
+impl From<&Failure> for JsonError {
+    fn from(failure: &Failure) -> Self {
+        match failure {
+            Failure::Input(err) => JsonError::new("input", err.clone()),
+            Failure::Solve(Error::Parse(err)) => JsonError {
+                line: Some(err.line),
+                column: Some(err.column),
+                text: Some(err.text.clone()),
+                ..JsonError::new("parse", err.reason.clone())
+            },
+            Failure::Solve(err @ Error::NoSolution { .. }) => {
+                JsonError::new("no_solution", err.to_string())
+            }
+            Failure::Solve(err @ Error::Overflow { .. }) => {
+                JsonError::new("overflow", err.to_string())
+            }
+            Failure::Solve(err @ Error::UnknownDay(_)) => {
+                JsonError::new("unknown_day", err.to_string())
+            }
+            Failure::Solve(err @ Error::UnknownPart(_)) => {
+                JsonError::new("unknown_part", err.to_string())
+            }
+        }
+    }
+}
+
+impl From<&Outcome> for JsonOutcome {
+    fn from(outcome: &Outcome) -> Self {
+        JsonOutcome {
+            day: outcome.day,
+            part: outcome.part,
+            answer: outcome.answer.as_ref().ok().map(|a| a.to_string()),
+            parse_time_ns: outcome.parse_time.map(|t| t.as_nanos()),
+            solve_time_ns: outcome.solve_time.map(|t| t.as_nanos()),
+            error: outcome.answer.as_ref().err().map(JsonError::from),
+        }
+    }
+}
+
+/// The single-line JSON object printed for `outcome` by `--json`.
+fn to_json(outcome: &Outcome) -> String {
+    serde_json::to_string(&JsonOutcome::from(outcome)).unwrap()
+}
+
+fn print_json(outcome: &Outcome) {
+    println!("{}", to_json(outcome));
+}
+
+fn run(options: Options) -> Result<bool, String> {
+    let parts: &[u8] = match options.part {
+        Some(1) => &[1],
+        Some(_) => &[2],
+        None => &[1, 2],
+    };
+    let print = if options.json { print_json } else { print_text };
+
+    let days: Vec<(&Day, Result<String, String>)> = if options.all {
+        registry::days()
+            .iter()
+            .map(|day| (day, read_input(&default_input(day.day()))))
+            .collect()
+    } else {
+        let number = options.day.unwrap_or_default();
+        let day =
+            registry::find(number).ok_or_else(|| format!("day {} is not implemented", number))?;
+        let path = options.input.unwrap_or_else(|| default_input(number));
+        vec![(day, read_input(&path))]
+    };
+
+    let answers = options.answers.as_deref().unwrap_or(DEFAULT_ANSWERS);
+    let mut store = match options.mode {
+        Mode::Solve => AnswerStore::default(),
+        _ => {
+            AnswerStore::load(answers).map_err(|err| format!("cannot load {}: {}", answers, err))?
+        }
+    };
+    if options.mode == Mode::Verify && store.records().is_empty() {
+        return Err(format!("no answers recorded in {}", answers));
+    }
+
+    let mut ok = true;
+    for (day, input) in days {
+        for outcome in run_day(day, parts, input) {
+            ok &= outcome.answer.is_ok();
+            match options.mode {
+                Mode::Solve => print(&outcome),
+                Mode::Record => {
+                    if let (Some(part), Some(hash), Ok(answer)) =
+                        (outcome.part, &outcome.input_hash, &outcome.answer)
+                    {
+                        store.record(outcome.day, part, hash, answer);
+                    }
+                    print(&outcome);
+                }
+                Mode::Verify => ok &= verify(&store, &outcome),
+            }
+        }
+    }
+
+    if options.mode == Mode::Record {
+        store
+            .save(answers)
+            .map_err(|err| format!("cannot save {}: {}", answers, err))?;
+    }
+    Ok(ok)
+}
+
+/// Compares an outcome with its recorded answer, returning `false` unless it has an answer
+/// and that answer matches the recorded one.
+fn verify(store: &AnswerStore, outcome: &Outcome) -> bool {
+    let (part, hash, answer) = match (outcome.part, &outcome.input_hash, &outcome.answer) {
+        (Some(part), Some(hash), Ok(answer)) => (part, hash, answer.to_string()),
+        _ => {
+            print_text(outcome);
+            return false;
+        }
+    };
+    let prefix = format!("Day {} - part {}", outcome.day, part);
+    match store.get(outcome.day, part, hash) {
+        Some(expected) if expected == answer => {
+            println!("{}: ok ({})", prefix, answer);
+            true
+        }
+        Some(expected) => {
+            println!(
+                "{}: CHANGED, expected {} but got {}",
+                prefix, expected, answer
+            );
+            false
+        }
+        None => {
+            println!("{}: MISSING, no recorded answer for {}", prefix, answer);
+            false
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let result = parse_args(args.into_iter()).and_then(run);
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc2020::ParseError;
+
+    #[test]
+    fn run_day_reports_no_solution_test() {
+        let day = registry::find(9).unwrap();
+        let outcomes = run_day(day, &[1, 2], Ok("1\n2\n3".to_string()));
+        assert_eq!(outcomes.len(), 2);
+        for (outcome, part) in outcomes.iter().zip(1..) {
+            assert_eq!(outcome.part, Some(part));
+            assert!(matches!(
+                outcome.answer,
+                Err(Failure::Solve(Error::NoSolution { day: 9, part: p })) if p == part
+            ));
+        }
+
+        let day = registry::find(5).unwrap();
+        let outcomes = run_day(day, &[1, 2], Ok("BFFFBBFRRR".to_string()));
+        assert_eq!(
+            outcomes[0].answer.as_ref().ok(),
+            Some(&Answer::Unsigned(567))
+        );
+        assert!(matches!(
+            outcomes[1].answer,
+            Err(Failure::Solve(Error::NoSolution { day: 5, part: 2 }))
+        ));
+    }
+
+    fn args(args: &str) -> Result<Options, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_args_test() {
+        let options = args("--day 3 --part 2 --input - --json").unwrap();
+        assert_eq!(options.day, Some(3));
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input.as_deref(), Some("-"));
+        assert!(options.json);
+        assert_eq!(args("--all --verify").unwrap().mode, Mode::Verify);
+        assert_eq!(args("--all --record --json").unwrap().mode, Mode::Record);
+
+        assert_eq!(args("").unwrap_err(), "either --day or --all is required");
+        assert_eq!(
+            args("--day 3 --part 3").unwrap_err(),
+            "part 3 does not exist"
+        );
+        assert_eq!(args("--day").unwrap_err(), "missing value for --day");
+    }
+
+    #[test]
+    fn parse_args_conflicts_test() {
+        let conflict = "--record cannot be combined with --verify";
+        assert_eq!(args("--all --record --verify").unwrap_err(), conflict);
+        assert_eq!(args("--all --verify --record").unwrap_err(), conflict);
+        assert_eq!(
+            args("--all --day 3").unwrap_err(),
+            "--all cannot be combined with --day"
+        );
+        assert_eq!(
+            args("--all --input -").unwrap_err(),
+            "--all cannot be combined with --input"
+        );
+        assert_eq!(
+            args("--day 3 --json --verify").unwrap_err(),
+            "--json cannot be combined with --verify"
+        );
+    }
+
+    #[test]
+    fn verify_test() {
+        let answer = Answer::Signed(-8);
+        let mut store = AnswerStore::default();
+        store.record(8, 2, "abc", &answer);
+
+        let solved = |day, answer| Outcome {
+            day,
+            input_hash: Some("abc".to_string()),
+            ..outcome(Some(2), answer)
+        };
+        assert!(verify(&store, &solved(8, Ok(answer))));
+        assert!(!verify(&store, &solved(8, Ok(Answer::Signed(8)))));
+        assert!(!verify(&store, &solved(9, Ok(answer))));
+        assert!(!verify(
+            &store,
+            &solved(
+                8,
+                Err(Failure::Solve(Error::NoSolution { day: 8, part: 2 }))
+            )
+        ));
+        assert!(!verify(
+            &store,
+            &outcome(None, Err(Failure::Input("missing".to_string())))
+        ));
+    }
+
+    fn outcome(part: Option<u8>, answer: Result<Answer, Failure>) -> Outcome {
+        Outcome {
+            day: 8,
+            part,
+            input_hash: None,
+            parse_time: Some(Duration::from_nanos(1500)),
+            solve_time: part.map(|_| Duration::from_nanos(42)),
+            answer,
+        }
+    }
+
+    #[test]
+    fn to_json_test() {
+        assert_eq!(
+            to_json(&outcome(Some(2), Ok(Answer::Signed(-8)))),
+            r#"{"day":8,"part":2,"answer":"-8","parse_time_ns":1500,"solve_time_ns":42,"error":null}"#
+        );
+
+        let err = ParseError::new(8, 2, 5, "4x", "expected a signed integer");
+        assert_eq!(
+            to_json(&outcome(None, Err(Failure::Solve(Error::Parse(err))))),
+            concat!(
+                r#"{"day":8,"part":null,"answer":null,"parse_time_ns":1500,"solve_time_ns":null,"#,
+                r#""error":{"kind":"parse","message":"expected a signed integer","line":2,"#,
+                r#""column":5,"text":"4x"}}"#
+            )
+        );
+
+        let input = Outcome {
+            parse_time: None,
+            ..outcome(
+                None,
+                Err(Failure::Input("cannot read day8.txt".to_string())),
+            )
+        };
+        assert_eq!(
+            to_json(&input),
+            concat!(
+                r#"{"day":8,"part":null,"answer":null,"parse_time_ns":null,"solve_time_ns":null,"#,
+                r#""error":{"kind":"input","message":"cannot read day8.txt"}}"#
+            )
+        );
+
+        let overflow = Failure::Solve(Error::Overflow { day: 8, part: 2 });
+        assert_eq!(
+            to_json(&outcome(Some(2), Err(overflow))),
+            concat!(
+                r#"{"day":8,"part":2,"answer":null,"parse_time_ns":1500,"solve_time_ns":42,"#,
+                r#""error":{"kind":"overflow","message":"day 8 part 2 overflows for this input"}}"#
+            )
+        );
+    }
+}
@@ -1,37 +1,261 @@
+use crate::input::lines;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashSet;
+use num::PrimInt;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+pub mod reconcile;
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Vec<u32> {
-    input.lines().map(|l| l.parse().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<i64>, ParseError> {
+    lines(1, input)
+        .map(|l| l.parse(l.text, "an integer"))
+        .collect()
 }
 
 #[aoc(day1, part1)]
-pub fn solve_part1(input: &[u32]) -> u32 {
-    let (x, y) = two_sum(input, 2020).unwrap();
-    x * y
+pub fn solve_part1(input: &[i64]) -> Result<i64, Unsolved> {
+    solve(input, 2)
 }
 
 #[aoc(day1, part2)]
-pub fn solve_part2(input: &[u32]) -> u32 {
-    for i in 0..(input.len() - 2) {
-        if let Some((x, y)) = two_sum(&input[i + 1..], 2020 - input[i]) {
-            return x * y * input[i];
+pub fn solve_part2(input: &[i64]) -> Result<i64, Unsolved> {
+    solve(input, 3)
+}
+
+/// The product of `k` entries summing to 2020.
+fn solve(input: &[i64], k: usize) -> Result<i64, Unsolved> {
+    k_sum(input, k, 2020)
+        .ok_or(Unsolved::NoSolution)?
+        .product()
+        .ok_or(Unsolved::Overflow)
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input).map(Answer::from)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input).map(Answer::from)
+    }
+}
+
+/// An integer type expense amounts can be stored as.
+///
+/// Sums are computed in `i128`, which holds any total of these types, so targets
+/// smaller than an entry or negative amounts never overflow.
+pub trait Amount: PrimInt + Debug {
+    fn widen(self) -> i128;
+}
+
+macro_rules! impl_amount {
+    ($($t:ty),*) => {
+        $(impl Amount for $t {
+            fn widen(self) -> i128 {
+                self as i128
+            }
+        })*
+    };
+}
+
+impl_amount!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Entries of an expense report that add up to a target, in the order they appear.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Combination<T> {
+    pub indices: Vec<usize>,
+    pub values: Vec<T>,
+}
+
+impl<T: Amount> Combination<T> {
+    fn new(nums: &[T], mut indices: Vec<usize>) -> Self {
+        indices.sort_unstable();
+        let values = indices.iter().map(|&i| nums[i]).collect();
+        Combination { indices, values }
+    }
+
+    /// The product of the values, or `None` if it doesn't fit in `T`.
+    pub fn product(&self) -> Option<T> {
+        self.values
+            .iter()
+            .try_fold(T::one(), |product, value| product.checked_mul(value))
+    }
+}
+
+/// Finds `k` distinct entries of `nums` that sum to `target`.
+///
+/// Pairs are found in one pass with a hash map; larger groups take the first
+/// combination from [`k_sums`].
+pub fn k_sum<T: Amount>(nums: &[T], k: usize, target: T) -> Option<Combination<T>> {
+    let indices = match k {
+        0 if target.is_zero() => Some(Vec::new()),
+        0 => None,
+        1 => nums.iter().position(|&x| x == target).map(|i| vec![i]),
+        2 => two_sum_indices(nums, target).map(|(i, j)| vec![i, j]),
+        _ => return k_sums(nums, k, target).next(),
+    };
+    indices.map(|indices| Combination::new(nums, indices))
+}
+
+/// Every distinct combination of `k` entries of `nums` that sums to `target`.
+///
+/// Combinations are distinct by value: a value that appears several times in `nums` can
+/// be used that many times, but swapping one occurrence for another doesn't make a new
+/// combination. Each combination reports the earliest occurrences of its values.
+pub fn k_sums<T: Amount>(nums: &[T], k: usize, target: T) -> KSums<'_, T> {
+    let mut sorted: Vec<usize> = (0..nums.len()).collect();
+    sorted.sort_by_key(|&i| nums[i]);
+    let mut sums = KSums {
+        nums,
+        sorted,
+        k,
+        target: target.widen(),
+        prefix: Vec::new(),
+        lo: 0,
+        hi: 0,
+        pending: None,
+        done: k < 2,
+    };
+    if k < 2 {
+        sums.pending = k_sum(nums, k, target);
+    } else {
+        sums.seek(0);
+    }
+    sums
+}
+
+/// Iterator returned by [`k_sums`].
+///
+/// Walks the entries in value order, fixing all but the last two members of a
+/// combination and finding the last two with a two-pointer scan, so only one
+/// combination is held at a time.
+#[derive(Debug, Clone)]
+pub struct KSums<'a, T> {
+    nums: &'a [T],
+    /// Indices into `nums`, ordered by value and then by index.
+    sorted: Vec<usize>,
+    k: usize,
+    target: i128,
+    /// Positions in `sorted` of the fixed members.
+    prefix: Vec<usize>,
+    /// The two-pointer window, `lo..hi`, over the positions after the prefix.
+    lo: usize,
+    hi: usize,
+    /// The single answer for `k < 2`, which has no pairs to scan.
+    pending: Option<Combination<T>>,
+    done: bool,
+}
+
+impl<T: Amount> KSums<'_, T> {
+    fn value(&self, pos: usize) -> i128 {
+        self.nums[self.sorted[pos]].widen()
+    }
+
+    /// What the members after the prefix must add up to.
+    fn remaining(&self) -> i128 {
+        self.target - self.prefix.iter().map(|&p| self.value(p)).sum::<i128>()
+    }
+
+    /// The first position after `pos` holding a different value.
+    fn next_distinct(&self, pos: usize) -> usize {
+        let mut next = pos + 1;
+        while next < self.sorted.len() && self.value(next) == self.value(pos) {
+            next += 1;
+        }
+        next
+    }
+
+    /// Fills the prefix starting from position `start`, backtracking to the next
+    /// distinct value of an earlier member when nothing fits, then opens the window.
+    fn seek(&mut self, mut start: usize) {
+        while self.prefix.len() < self.k - 2 {
+            let needed = self.k - self.prefix.len();
+            // The smallest sum the rest of the combination can have from here; values
+            // only grow, so if it's too big no later start will fit either.
+            let fits = start + needed <= self.sorted.len()
+                && (start..start + needed).map(|p| self.value(p)).sum::<i128>() <= self.remaining();
+            if fits {
+                self.prefix.push(start);
+                start += 1;
+            } else {
+                match self.prefix.pop() {
+                    Some(p) => start = self.next_distinct(p),
+                    None => {
+                        self.done = true;
+                        return;
+                    }
+                }
+            }
         }
+        self.lo = start;
+        self.hi = self.sorted.len();
     }
-    unreachable!()
 }
 
-fn two_sum(nums: &[u32], sum: u32) -> Option<(u32, u32)> {
-    if nums.len() < 2 {
-        return None;
+impl<T: Amount> Iterator for KSums<'_, T> {
+    type Item = Combination<T>;
+
+    fn next(&mut self) -> Option<Combination<T>> {
+        if let Some(combination) = self.pending.take() {
+            return Some(combination);
+        }
+        while !self.done {
+            let remaining = self.remaining();
+            while self.lo + 1 < self.hi {
+                let (lo, hi) = (self.lo, self.hi - 1);
+                match (self.value(lo) + self.value(hi)).cmp(&remaining) {
+                    Ordering::Less => self.lo = self.next_distinct(lo),
+                    Ordering::Greater => self.hi -= 1,
+                    Ordering::Equal => {
+                        let mut first_hi = hi;
+                        while first_hi > lo + 1 && self.value(first_hi - 1) == self.value(hi) {
+                            first_hi -= 1;
+                        }
+                        self.lo = self.next_distinct(lo);
+                        self.hi = first_hi;
+                        let positions = self.prefix.iter().copied().chain([lo, first_hi]);
+                        let indices = positions.map(|p| self.sorted[p]).collect();
+                        return Some(Combination::new(self.nums, indices));
+                    }
+                }
+            }
+            match self.prefix.pop() {
+                Some(p) => {
+                    let start = self.next_distinct(p);
+                    self.seek(start);
+                }
+                None => self.done = true,
+            }
+        }
+        None
     }
-    let mut set = HashSet::new();
-    for &x in nums.iter() {
-        if set.contains(&(sum - x)) {
-            return Some((sum - x, x));
+}
+
+pub fn two_sum<T: Amount>(nums: &[T], sum: T) -> Option<(T, T)> {
+    two_sum_indices(nums, sum).map(|(i, j)| (nums[i], nums[j]))
+}
+
+fn two_sum_indices<T: Amount>(nums: &[T], sum: T) -> Option<(usize, usize)> {
+    let mut seen = HashMap::new();
+    for (j, &x) in nums.iter().enumerate() {
+        if let Some(&i) = seen.get(&(sum.widen() - x.widen())) {
+            return Some((i, j));
         }
-        set.insert(x);
+        seen.entry(x.widen()).or_insert(j);
     }
     None
 }
@@ -39,6 +263,8 @@ fn two_sum(nums: &[u32], sum: u32) -> Option<(u32, u32)> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     #[test]
     fn two_sum_test() {
@@ -52,4 +278,155 @@ mod test {
         // Non existent sum
         assert!(two_sum(&[1, 2, 3, 4], 8).is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn k_sum_test() {
+        let report = [1721, 979, 366, 299, 675, 1456];
+        let pair = k_sum(&report, 2, 2020).unwrap();
+        assert_eq!(pair.indices, vec![0, 3]);
+        assert_eq!(pair.values, vec![1721, 299]);
+        assert_eq!(pair.product(), Some(514579));
+
+        let triple = k_sum(&report, 3, 2020).unwrap();
+        assert_eq!(triple.values, vec![979, 366, 675]);
+        assert_eq!(triple.product(), Some(241861950));
+
+        assert_eq!(
+            k_sum(&report, 4, 979 + 366 + 299 + 675).unwrap().indices,
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(k_sum(&report, 1, 366).unwrap().indices, vec![2]);
+        assert!(k_sum(&report, 0, 0).unwrap().indices.is_empty());
+        assert!(k_sum(&report, 2, 1010).is_none());
+        assert!(k_sum(&[1010, 1010], 2, 2020).is_some());
+        assert!(k_sum(&report, 7, 0).is_none());
+    }
+
+    #[test]
+    fn k_sums_test() {
+        let pairs: Vec<_> = k_sums(&[5, 15, 5, 10, 10, 0, 20], 2, 20)
+            .map(|c| c.indices)
+            .collect();
+        assert_eq!(pairs, vec![vec![5, 6], vec![0, 1], vec![3, 4]]);
+
+        let triples: Vec<_> = k_sums(&[1, 1, 1, 2, 2, 3], 3, 5)
+            .map(|c| c.values)
+            .collect();
+        assert_eq!(triples, vec![vec![1, 1, 3], vec![1, 2, 2]]);
+
+        assert_eq!(k_sums(&[1010], 2, 2020).count(), 0);
+        assert_eq!(k_sums(&[1010, 1010, 1010], 2, 2020).count(), 1);
+        assert_eq!(k_sums(&[3, 4], 1, 4).count(), 1);
+        assert_eq!(k_sums(&[], 0, 0).count(), 1);
+    }
+
+    #[test]
+    fn amount_types_test() {
+        // Entries larger than the target and negative amounts.
+        assert_eq!(two_sum(&[5000u32, 20, 2000], 2020), Some((20, 2000)));
+        assert_eq!(two_sum(&[3000i64, -980, 7], 2020), Some((3000, -980)));
+        assert_eq!(
+            k_sum(&[-5i8, 100, 127, -128], 3, -6).unwrap().values,
+            vec![-5, 127, -128]
+        );
+
+        // Sums past the type's range still compare correctly.
+        let wide = [u64::MAX, u64::MAX - 1, 1];
+        assert_eq!(k_sum(&wide, 2, u64::MAX).unwrap().indices, vec![1, 2]);
+        assert_eq!(
+            k_sum(&wide, 2, u64::MAX).unwrap().product(),
+            Some(u64::MAX - 1)
+        );
+        assert_eq!(k_sum(&wide, 2, 0), None);
+        assert_eq!(k_sum(&[200u8, 50], 2, 250).unwrap().product(), None);
+    }
+
+    #[test]
+    fn solve_test() {
+        let report = input_generator("1721\n979\n366\n299\n675\n1456").unwrap();
+        assert_eq!(solve_part1(&report), Ok(514579));
+        assert_eq!(solve_part2(&report), Ok(241861950));
+
+        let report = input_generator("2021\n-1\n5000").unwrap();
+        assert_eq!(solve_part1(&report), Ok(-2021));
+        assert_eq!(solve_part2(&report), Err(Unsolved::NoSolution));
+
+        let report = input_generator("4611686018427387904\n-4611686018427385884").unwrap();
+        assert_eq!(solve_part1(&report), Err(Unsolved::Overflow));
+    }
+
+    #[test]
+    fn input_generator_test() {
+        assert_eq!(input_generator("1721\n979\n"), Ok(vec![1721, 979]));
+        assert_eq!(
+            input_generator("1721\n97x").unwrap_err(),
+            ParseError::new(1, 2, 1, "97x", "expected an integer")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn k_sum_matches_brute_force(
+            nums in prop::collection::vec(0u32..50, 0..10),
+            k in 0usize..5,
+            target in 0u32..150,
+        ) {
+            let expected = (0..nums.len())
+                .combinations(k)
+                .any(|c| c.iter().map(|&i| nums[i]).sum::<u32>() == target);
+            match k_sum(&nums, k, target) {
+                Some(found) => {
+                    prop_assert!(expected);
+                    prop_assert_eq!(found.values.iter().sum::<u32>(), target);
+                    prop_assert!(found.indices.windows(2).all(|w| w[0] < w[1]));
+                    prop_assert_eq!(found.indices.len(), k);
+                }
+                None => prop_assert!(!expected),
+            }
+        }
+
+        #[test]
+        fn k_sums_matches_brute_force(
+            nums in prop::collection::vec(-20i32..20, 0..10),
+            k in 0usize..5,
+            target in -60i32..60,
+        ) {
+            let expected: Vec<Vec<i32>> = (0..nums.len())
+                .combinations(k)
+                .map(|c| c.iter().map(|&i| nums[i]).sorted().collect::<Vec<_>>())
+                .filter(|values| values.iter().sum::<i32>() == target)
+                .sorted()
+                .dedup()
+                .collect();
+            let found: Vec<Combination<i32>> = k_sums(&nums, k, target).collect();
+            for combination in &found {
+                prop_assert_eq!(combination.indices.len(), k);
+                prop_assert!(combination.indices.windows(2).all(|w| w[0] < w[1]));
+            }
+            let found: Vec<Vec<i32>> = found
+                .into_iter()
+                .map(|c| c.values.into_iter().sorted().collect())
+                .sorted()
+                .collect();
+            prop_assert_eq!(found, expected);
+        }
+
+        #[test]
+        fn two_sum_matches_nested_loops(
+            nums in prop::collection::vec(0u32..100, 0..20),
+            sum in 0u32..200,
+        ) {
+            let expected = (0..nums.len())
+                .any(|i| (i + 1..nums.len()).any(|j| nums[i] + nums[j] == sum));
+            match two_sum(&nums, sum) {
+                Some((x, y)) => {
+                    prop_assert!(expected);
+                    prop_assert_eq!(x + y, sum);
+                    let i = nums.iter().position(|&n| n == x).unwrap();
+                    prop_assert!(nums.iter().enumerate().any(|(j, &n)| j != i && n == y));
+                }
+                None => prop_assert!(!expected),
+            }
+        }
+    }
+}
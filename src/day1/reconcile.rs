@@ -0,0 +1,216 @@
+//! Queries for reconciling an expense report against a total that no exact `k`-entry
+//! combination may hit.
+
+use super::{Amount, Combination};
+use std::collections::HashMap;
+
+/// The `k` entries of `nums` whose sum is closest to `target`, or `None` if there are
+/// fewer than `k` entries.
+///
+/// When two sums miss by the same amount, the smaller one wins.
+pub fn closest_k_sum<T: Amount>(nums: &[T], k: usize, target: T) -> Option<Combination<T>> {
+    if k > nums.len() {
+        return None;
+    }
+    let mut sorted: Vec<usize> = (0..nums.len()).collect();
+    sorted.sort_by_key(|&i| nums[i]);
+    let mut search = Closest {
+        nums,
+        sorted,
+        prefix: Vec::new(),
+        best: None,
+    };
+    search.search(k, 0, target.widen());
+    let (_, indices) = search.best?;
+    Some(Combination::new(nums, indices))
+}
+
+/// Exhaustive search for [`closest_k_sum`] over entries sorted by value.
+struct Closest<'a, T> {
+    nums: &'a [T],
+    sorted: Vec<usize>,
+    /// Positions in `sorted` of the members fixed so far.
+    prefix: Vec<usize>,
+    /// How far the best combination is from the target, and its indices.
+    best: Option<(i128, Vec<usize>)>,
+}
+
+impl<T: Amount> Closest<'_, T> {
+    fn value(&self, pos: usize) -> i128 {
+        self.nums[self.sorted[pos]].widen()
+    }
+
+    fn is_exact(&self) -> bool {
+        matches!(self.best, Some((0, _)))
+    }
+
+    /// Records the prefix plus `rest` if it beats the best so far by `miss`, the
+    /// difference between its sum and the target.
+    fn offer(&mut self, miss: i128, rest: &[usize]) {
+        let better = match self.best {
+            Some((best, _)) => (miss.abs(), miss) < (best.abs(), best),
+            None => true,
+        };
+        if better {
+            let positions = self.prefix.iter().chain(rest);
+            self.best = Some((miss, positions.map(|&p| self.sorted[p]).collect()));
+        }
+    }
+
+    /// Picks `k` more members from positions `start..` to add up to `remaining`.
+    fn search(&mut self, k: usize, start: usize, remaining: i128) {
+        match k {
+            0 => self.offer(-remaining, &[]),
+            1 => {
+                for pos in start..self.sorted.len() {
+                    self.offer(self.value(pos) - remaining, &[pos]);
+                }
+            }
+            2 => {
+                let (mut lo, mut hi) = (start, self.sorted.len() - 1);
+                while lo < hi && !self.is_exact() {
+                    let miss = self.value(lo) + self.value(hi) - remaining;
+                    self.offer(miss, &[lo, hi]);
+                    if miss < 0 {
+                        lo += 1;
+                    } else {
+                        hi -= 1;
+                    }
+                }
+            }
+            _ => {
+                let mut pos = start;
+                while pos + k <= self.sorted.len() && !self.is_exact() {
+                    self.prefix.push(pos);
+                    self.search(k - 1, pos + 1, remaining - self.value(pos));
+                    self.prefix.pop();
+                    // Equal values at the same place give the same combinations.
+                    let value = self.value(pos);
+                    while pos < self.sorted.len() && self.value(pos) == value {
+                        pos += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A non-empty subset of `nums`, of any size, that sums to `target`.
+///
+/// Builds every reachable sum one entry at a time, remembering for each sum the entry
+/// that first reached it, so the cost grows with the number of distinct sums rather
+/// than the number of subsets.
+pub fn subset_sum<T: Amount>(nums: &[T], target: T) -> Option<Combination<T>> {
+    let target = target.widen();
+    // For each reachable sum, the entry that reached it and the sum it was added to.
+    let mut reached: HashMap<i128, (usize, Option<i128>)> = HashMap::new();
+    for (i, value) in nums.iter().enumerate() {
+        let value = value.widen();
+        let new: Vec<_> = reached
+            .keys()
+            .map(|&sum| (sum + value, Some(sum)))
+            .chain(Some((value, None)))
+            .collect();
+        for (sum, previous) in new {
+            reached.entry(sum).or_insert((i, previous));
+        }
+        if reached.contains_key(&target) {
+            break;
+        }
+    }
+
+    let mut indices = Vec::new();
+    let mut sum = Some(target);
+    while let Some(s) = sum {
+        let &(i, previous) = reached.get(&s)?;
+        indices.push(i);
+        sum = previous;
+    }
+    Some(Combination::new(nums, indices))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    #[test]
+    fn closest_k_sum_test() {
+        let report = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(
+            closest_k_sum(&report, 2, 2020).unwrap().values,
+            vec![1721, 299]
+        );
+        assert_eq!(
+            closest_k_sum(&report, 2, 2000).unwrap().values,
+            vec![1721, 299]
+        );
+        assert_eq!(
+            closest_k_sum(&report, 3, 1000).unwrap().values,
+            vec![366, 299, 675]
+        );
+        assert_eq!(closest_k_sum(&report, 1, 0).unwrap().values, vec![299]);
+
+        // 10 and 20 miss by the same amount; the smaller sum wins.
+        assert_eq!(closest_k_sum(&[20, 10], 1, 15).unwrap().values, vec![10]);
+        assert_eq!(
+            closest_k_sum(&[-7i64, 8], 2, 5).unwrap().indices,
+            vec![0, 1]
+        );
+        assert!(closest_k_sum(&report, 0, 5).unwrap().values.is_empty());
+        assert!(closest_k_sum(&report, 7, 5).is_none());
+    }
+
+    #[test]
+    fn subset_sum_test() {
+        let report = [1721, 979, 366, 299, 675, 1456];
+        let subset = subset_sum(&report, 979 + 299 + 1456).unwrap();
+        assert_eq!(subset.values.iter().sum::<i32>(), 979 + 299 + 1456);
+        assert_eq!(subset_sum(&report, 366).unwrap().indices, vec![2]);
+        assert!(subset_sum(&report, 1).is_none());
+        assert!(subset_sum(&report, 0).is_none());
+        assert_eq!(subset_sum(&[5i8, -5], 0).unwrap().indices, vec![0, 1]);
+        assert_eq!(subset_sum(&[u64::MAX, u64::MAX], 0), None);
+    }
+
+    proptest! {
+        #[test]
+        fn closest_k_sum_matches_brute_force(
+            nums in prop::collection::vec(-30i32..30, 0..9),
+            k in 0usize..5,
+            target in -80i32..80,
+        ) {
+            let best = (0..nums.len())
+                .combinations(k)
+                .map(|c| c.iter().map(|&i| nums[i]).sum::<i32>() - target)
+                .min_by_key(|&miss| (miss.abs(), miss));
+            let found = closest_k_sum(&nums, k, target);
+            prop_assert_eq!(found.is_some(), best.is_some());
+            if let (Some(found), Some(best)) = (found, best) {
+                prop_assert_eq!(found.indices.len(), k);
+                prop_assert!(found.indices.windows(2).all(|w| w[0] < w[1]));
+                prop_assert_eq!(found.values.iter().sum::<i32>() - target, best);
+            }
+        }
+
+        #[test]
+        fn subset_sum_matches_brute_force(
+            nums in prop::collection::vec(-30i32..30, 0..10),
+            target in -80i32..80,
+        ) {
+            let expected = (1..=nums.len())
+                .flat_map(|k| (0..nums.len()).combinations(k))
+                .any(|c| c.iter().map(|&i| nums[i]).sum::<i32>() == target);
+            match subset_sum(&nums, target) {
+                Some(found) => {
+                    prop_assert!(expected);
+                    prop_assert!(!found.indices.is_empty());
+                    prop_assert!(found.indices.windows(2).all(|w| w[0] < w[1]));
+                    prop_assert_eq!(found.values.iter().sum::<i32>(), target);
+                }
+                None => prop_assert!(!expected),
+            }
+        }
+    }
+}
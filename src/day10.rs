@@ -1,13 +1,28 @@
+use crate::input::lines;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashMap;
 
 #[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Vec<usize> {
-    let mut input: Vec<usize> = input.lines().map(|l| l.parse().unwrap()).collect();
+pub fn input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    let mut input = lines(10, input)
+        .map(|l| match l.parse(l.text, "an adapter joltage")? {
+            joltage if joltage > usize::MAX - 3 => {
+                Err(l.error(l.text, "expected a joltage the device can exceed by 3"))
+            }
+            joltage => Ok(joltage),
+        })
+        .collect::<Result<Vec<usize>, _>>()?;
+    let max = input
+        .iter()
+        .max()
+        .copied()
+        .ok_or_else(|| ParseError::empty(10))?;
     input.push(0);
-    input.push(3 + input.iter().max().copied().unwrap());
+    input.push(3 + max);
     input.sort_unstable();
-    input
+    Ok(input)
 }
 
 #[aoc(day10, part1)]
@@ -32,7 +47,27 @@ pub fn solve_part2(input: &[usize]) -> usize {
     rec(input, 0, &mut HashMap::new())
 }
 
-fn rec(ads: &[usize], i: usize, cache: &mut HashMap<usize, usize>) -> usize {
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part2(input).into())
+    }
+}
+
+pub fn rec(ads: &[usize], i: usize, cache: &mut HashMap<usize, usize>) -> usize {
     if cache.contains_key(&i) {
         return *cache.get(&i).unwrap();
     }
@@ -43,7 +78,7 @@ fn rec(ads: &[usize], i: usize, cache: &mut HashMap<usize, usize>) -> usize {
     let mut res = 0;
     for j in (i + 1)..=(i + 3) {
         if j < ads.len() && ads[j] - ads[i] <= 3 {
-            res += rec(&ads, j, cache);
+            res += rec(ads, j, cache);
         }
     }
     cache.insert(i, res);
@@ -54,11 +89,53 @@ fn rec(ads: &[usize], i: usize, cache: &mut HashMap<usize, usize>) -> usize {
 #[cfg(test)]
 mod test {
     use super::*;
+    use itertools::join;
+    use proptest::prelude::*;
 
     static EXAMPLE_1: &[usize] = &[16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
 
     #[test]
     fn solve_part1_test() {
-        assert_eq!(solve_part1(EXAMPLE_1), 35);
+        let adapters = input_generator(join(EXAMPLE_1, "\n").as_str()).unwrap();
+        assert_eq!(solve_part1(&adapters), 35);
+    }
+
+    #[test]
+    fn input_generator_test() {
+        assert_eq!(input_generator("").unwrap_err(), ParseError::empty(10));
+        assert!(input_generator("1\n-2").is_err());
+        assert!(input_generator(&usize::MAX.to_string()).is_err());
+    }
+
+    /// Counts arrangements by trying every subset of the adapters between the outlet
+    /// and the device.
+    fn count_arrangements(adapters: &[usize]) -> usize {
+        let inner = &adapters[1..adapters.len() - 1];
+        (0..1u32 << inner.len())
+            .filter(|subset| {
+                let chain: Vec<usize> = std::iter::once(adapters[0])
+                    .chain(
+                        (0..inner.len())
+                            .filter(|i| subset >> i & 1 == 1)
+                            .map(|i| inner[i]),
+                    )
+                    .chain(std::iter::once(adapters[adapters.len() - 1]))
+                    .collect();
+                chain.windows(2).all(|w| w[1] - w[0] <= 3)
+            })
+            .count()
+    }
+
+    proptest! {
+        #[test]
+        fn rec_matches_exhaustive_enumeration(
+            joltages in prop::collection::btree_set(1usize..30, 1..12),
+        ) {
+            let adapters = input_generator(join(&joltages, "\n").as_str()).unwrap();
+            prop_assert_eq!(
+                rec(&adapters, 0, &mut HashMap::new()),
+                count_arrangements(&adapters)
+            );
+        }
     }
 }
@@ -1,25 +1,17 @@
+use crate::grid::{Grid, NEIGHBOURS8};
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::fmt;
 use std::fmt::Formatter;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-enum Seat {
+pub enum Seat {
     Occupied,
     Floor,
     Empty,
 }
 
-const DIRS: [(isize, isize); 8] = [
-    (0, 1),
-    (0, -1),
-    (1, 1),
-    (1, -1),
-    (-1, 1),
-    (-1, -1),
-    (1, 0),
-    (-1, 0),
-];
-
 impl fmt::Display for Seat {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
@@ -34,93 +26,75 @@ impl fmt::Display for Seat {
     }
 }
 
-#[derive(Clone, Eq, PartialEq)]
-pub struct Map(Vec<Vec<Seat>>);
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Map(Grid<Seat>);
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        for row in &self.0 {
-            for seat in row {
-                write!(f, "{}", seat)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        self.0.fmt(f)
     }
 }
 
 impl Map {
-    fn get_seat(&self, i: isize, j: isize) -> Option<Seat> {
-        if i < 0 || j < 0 || i >= self.0.len() as isize || j >= self.0[0].len() as isize {
-            return None;
-        }
-        Some((self.0)[i as usize][j as usize].clone())
+    /// Builds a map from rows of seats, which must all have the same length.
+    pub fn new(rows: Vec<Vec<Seat>>) -> Self {
+        Map(Grid::from_rows(rows))
     }
 
-    fn num_rows(&self) -> usize {
-        self.0.len()
+    pub fn grid(&self) -> &Grid<Seat> {
+        &self.0
     }
 
-    fn num_cols(&self) -> usize {
-        if self.num_rows() == 0 {
-            return 0;
-        }
-        self.0[0].len()
+    pub fn get_seat(&self, i: isize, j: isize) -> Option<Seat> {
+        self.0.get(j, i).cloned()
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.0.height()
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.0.width()
     }
 
     fn set_seat(&mut self, i: usize, j: usize, seat: Seat) {
-        self.0[i][j] = seat
+        self.0.set(j, i, seat)
     }
 
     fn count_adjacent_seats(&self, i: usize, j: usize, seat_type: Seat) -> usize {
-        DIRS.iter().fold(0, |sum, &(di, dj)| {
-            match self.get_seat(i as isize + di, j as isize + dj) {
-                Some(seat) if seat == seat_type => sum + 1,
-                _ => sum,
-            }
-        })
+        self.0
+            .neighbours8(j, i)
+            .filter(|&pos| self.0[pos] == seat_type)
+            .count()
     }
 
     fn count_adjacent_seats_visible(&self, i: usize, j: usize, seat_type: Seat) -> usize {
-        let mut ct = 0;
-
-        for &(di, dj) in DIRS.iter() {
-            let (mut ni, mut nj) = (i as isize + di, j as isize + dj);
-            while self.get_seat(ni, nj) == Some(Seat::Floor) {
-                let (ni2, nj2) = (ni + di, nj + dj);
-                ni = ni2;
-                nj = nj2;
-            }
-
-            if let Some(seat) = self.get_seat(ni, nj) {
-                if seat == seat_type {
-                    ct += 1;
-                }
-            }
-        }
-        ct
+        NEIGHBOURS8
+            .iter()
+            .filter_map(|&(dj, di)| {
+                self.0
+                    .ray(j, i, dj, di)
+                    .map(|pos| &self.0[pos])
+                    .find(|&seat| *seat != Seat::Floor)
+            })
+            .filter(|&seat| *seat == seat_type)
+            .count()
     }
 
-    fn count_seats(&self, seat_type: Seat) -> usize {
-        self.0.iter().flatten().filter(|&x| x == &seat_type).count()
+    pub fn count_seats(&self, seat_type: Seat) -> usize {
+        self.0.iter().filter(|&x| x == &seat_type).count()
     }
 }
 
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Map {
-    Map(input
-        .lines()
-        .map(|l| {
-            l.bytes()
-                .map(|b| match b {
-                    b'.' => Seat::Floor,
-                    b'L' => Seat::Empty,
-                    b'#' => Seat::Occupied,
-                    _ => unreachable!(),
-                })
-                .collect()
-        })
-        .collect())
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    let grid = Grid::parse(11, input, "`.`, `L` or `#`", |c| match c {
+        '.' => Some(Seat::Floor),
+        'L' => Some(Seat::Empty),
+        '#' => Some(Seat::Occupied),
+        _ => None,
+    })?;
+    Ok(Map(grid))
 }
 
 #[aoc(day11, part1)]
@@ -133,7 +107,27 @@ pub fn solve_part2(map: &Map) -> usize {
     simulate(map, tick2)
 }
 
-fn simulate<F>(map: &Map, tick: F) -> usize
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part2(input).into())
+    }
+}
+
+pub fn simulate<F>(map: &Map, tick: F) -> usize
 where
     F: Fn(&Map) -> Map,
 {
@@ -147,7 +141,7 @@ where
     curr.count_seats(Seat::Occupied)
 }
 
-fn tick1(map: &Map) -> Map {
+pub fn tick1(map: &Map) -> Map {
     let mut new_map = map.clone();
 
     for i in 0..map.num_rows() {
@@ -170,7 +164,7 @@ fn tick1(map: &Map) -> Map {
     new_map
 }
 
-fn tick2(map: &Map) -> Map {
+pub fn tick2(map: &Map) -> Map {
     let mut new_map = map.clone();
 
     for i in 0..map.num_rows() {
@@ -201,7 +195,7 @@ mod test {
     use lazy_static::lazy_static;
 
     lazy_static! {
-        static ref MAP: Map = Map(vec![
+        static ref MAP: Map = Map::new(vec![
             vec![Floor, Occupied, Occupied, Floor, Occupied, Occupied, Floor],
             vec![Occupied, Floor, Occupied, Floor, Occupied, Floor, Occupied],
             vec![Occupied, Occupied, Floor, Floor, Floor, Occupied, Occupied],
@@ -221,4 +215,15 @@ mod test {
     fn solve_part2_test() {
         assert_eq!(solve_part2(&MAP), 9);
     }
+
+    #[test]
+    fn input_generator_test() {
+        let map = input_generator(".#\nL.").unwrap();
+        assert_eq!(map.to_string(), ".#\nL.\n");
+
+        let err = input_generator(".#\nLx").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+
+        assert!(input_generator(".#\nL").is_err());
+    }
 }
@@ -1,5 +1,8 @@
+use crate::input::{lines, Line};
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
 pub type Pos = (isize, isize);
 
@@ -28,17 +31,19 @@ impl TryInto<Pos> for Direction {
     }
 }
 
-impl From<char> for Direction {
-    fn from(c: char) -> Direction {
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Direction, Self::Error> {
         match c {
-            'N' => Direction::North,
-            'E' => Direction::East,
-            'S' => Direction::South,
-            'W' => Direction::West,
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            'F' => Direction::Forward,
-            _ => unreachable!(),
+            'N' => Ok(Direction::North),
+            'E' => Ok(Direction::East),
+            'S' => Ok(Direction::South),
+            'W' => Ok(Direction::West),
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            'F' => Ok(Direction::Forward),
+            _ => Err(c),
         }
     }
 }
@@ -87,86 +92,174 @@ impl Default for Ferry {
     }
 }
 
-impl Ferry {
-    fn move_ferry(&mut self, dir: Pos, amt: usize) {
-        let (dx, dy) = dir;
+/// `(x, y)` moved `amt` times by `(dx, dy)`, or `None` if that overflows.
+fn offset((x, y): Pos, (dx, dy): Pos, amt: usize) -> Option<Pos> {
+    let amt = isize::try_from(amt).ok()?;
+    Some((
+        x.checked_add(dx.checked_mul(amt)?)?,
+        y.checked_add(dy.checked_mul(amt)?)?,
+    ))
+}
 
-        self.x += dx * amt as isize;
-        self.y += dy * amt as isize;
+impl Ferry {
+    fn move_ferry(&mut self, dir: Pos, amt: usize) -> Option<()> {
+        let (x, y) = offset((self.x, self.y), dir, amt)?;
+        self.x = x;
+        self.y = y;
+        Some(())
     }
 
-    fn move_waypoint(&mut self, dir: Pos, amt: usize) {
-        let (dx, dy) = dir;
-
-        self.waypoint.x_off += dx * amt as isize;
-        self.waypoint.y_off += dy * amt as isize;
+    fn move_waypoint(&mut self, dir: Pos, amt: usize) -> Option<()> {
+        let (x_off, y_off) = offset((self.waypoint.x_off, self.waypoint.y_off), dir, amt)?;
+        self.waypoint.x_off = x_off;
+        self.waypoint.y_off = y_off;
+        Some(())
     }
 
-    fn forward(&mut self, amt: usize) {
-        let dx = self.waypoint.x_off;
-        let dy = self.waypoint.y_off;
-
-        self.x += dx * amt as isize;
-        self.y += dy * amt as isize;
+    fn forward(&mut self, amt: usize) -> Option<()> {
+        let dir = (self.waypoint.x_off, self.waypoint.y_off);
+        self.move_ferry(dir, amt)
     }
 
-    fn rotate_waypoint(&mut self, dir: Direction, amt: usize) {
+    fn rotate_waypoint(&mut self, dir: Direction, amt: usize) -> Option<()> {
         let amt = match dir {
-            Direction::Left => amt,
-            Direction::Right => 4 - amt,
+            Direction::Left => amt % 4,
+            Direction::Right => (4 - amt % 4) % 4,
             _ => unreachable!(),
         };
 
         for _ in 0..amt {
             let x = self.waypoint.x_off;
 
-            self.waypoint.x_off = -self.waypoint.y_off;
+            self.waypoint.x_off = self.waypoint.y_off.checked_neg()?;
             self.waypoint.y_off = x;
         }
+        Some(())
     }
 
-    pub fn step(&mut self, action: Action, part1: bool) {
+    /// Carries out `action`, moving the waypoint instead of the ferry for part 2, or
+    /// returns `None` if a position overflows.
+    pub fn step(&mut self, action: Action, part1: bool) -> Option<()> {
         let Action { dir, amount: amt } = action;
         match dir {
             Direction::Left | Direction::Right => self.rotate_waypoint(dir, amt),
             Direction::Forward => self.forward(amt),
             _ if !part1 => self.move_waypoint(dir.try_into().unwrap(), amt),
             _ => self.move_ferry(dir.try_into().unwrap(), amt),
-        };
+        }
+    }
+
+    /// How far the ferry is from where it started, or `None` if that overflows.
+    fn distance(&self) -> Option<usize> {
+        self.x.unsigned_abs().checked_add(self.y.unsigned_abs())
     }
 }
 
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Vec<Action> {
-    input
-        .lines()
-        .map(|l| {
-            let dir = Direction::from(l.chars().next().unwrap());
-            let amount = l[1..].parse().unwrap();
-            Action::new(dir, amount)
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Action>, ParseError> {
+    lines(12, input).map(parse_action).collect()
+}
+
+fn parse_action(line: Line) -> Result<Action, ParseError> {
+    let mut chars = line.text.chars();
+    let dir = chars
+        .next()
+        .ok_or_else(|| line.error(line.text, "expected an action"))
+        .and_then(|c| {
+            Direction::try_from(c).map_err(|_| line.error(line.text, "expected one of `NESWLRF`"))
+        })?;
+    let amount = chars.as_str();
+    Ok(Action::new(dir, line.parse(amount, "an unsigned amount")?))
 }
 
 #[aoc(day12, part1)]
-pub fn solve_part1(input: &[Action]) -> usize {
-    let mut ferry = Ferry::default();
-    ferry.waypoint = WayPoint { x_off: 1, y_off: 0 };
+pub fn solve_part1(input: &[Action]) -> Option<usize> {
+    let mut ferry = Ferry {
+        waypoint: WayPoint { x_off: 1, y_off: 0 },
+        ..Default::default()
+    };
 
     for action in input {
-        ferry.step(*action, true);
+        ferry.step(*action, true)?;
     }
 
-    ferry.x.abs() as usize + ferry.y.abs() as usize
+    ferry.distance()
 }
 
 #[aoc(day12, part2)]
-pub fn solve_part2(input: &[Action]) -> usize {
+pub fn solve_part2(input: &[Action]) -> Option<usize> {
     let mut ferry = Ferry::default();
 
     for action in input {
-        ferry.step(*action, false);
+        ferry.step(*action, false)?;
     }
 
-    ferry.x.abs() as usize + ferry.y.abs() as usize
+    ferry.distance()
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Vec<Action>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "F10\nN3\nF7\nR90\nF11";
+
+    #[test]
+    fn solve_part1_test() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(25));
+    }
+
+    #[test]
+    fn solve_part2_test() {
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(286));
+
+        let input = input_generator("F10\nN3\nF7\nR450\nF11").unwrap();
+        assert_eq!(solve_part2(&input), Some(286));
+        let input = input_generator("F10\nN3\nF7\nL630\nF11").unwrap();
+        assert_eq!(solve_part2(&input), Some(286));
+    }
+
+    #[test]
+    fn overflow_test() {
+        let input = input_generator("N9223372036854775807\nN1").unwrap();
+        assert_eq!(solve_part1(&input), None);
+        let input = input_generator("F18446744073709551615").unwrap();
+        assert_eq!(solve_part1(&input), None);
+        assert_eq!(solve_part2(&input), None);
+        let input = input_generator("N9223372036854775806\nF1\nE9223372036854775807\nF1");
+        assert_eq!(solve_part2(&input.unwrap()), None);
+        let input = input_generator("S9223372036854775807\nW9223372036854775807").unwrap();
+        assert_eq!(solve_part1(&input), Some(usize::MAX - 1));
+    }
+
+    #[test]
+    fn input_generator_test() {
+        let err = input_generator("F10\nX3").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        assert!(input_generator("F").is_err());
+    }
 }
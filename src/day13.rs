@@ -1,72 +1,142 @@
+use crate::input::lines;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use num::Integer;
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> (u64, Vec<(u64, u64)>) {
-    let mut lines = input.lines();
-    let arrival = lines.next().unwrap().parse().unwrap();
-    let times: Vec<(u64, u64)> = lines
+pub fn input_generator(input: &str) -> Result<(u64, Vec<(u64, u64)>), ParseError> {
+    let mut lines = lines(13, input);
+    let line = lines.next().ok_or_else(|| ParseError::empty(13))?;
+    let arrival = line.parse(line.text, "an arrival time")?;
+    let line = lines
         .next()
-        .unwrap()
-        .split(',')
-        .enumerate()
-        .filter_map(|(i, x)| match x {
-            "x" => None,
-            _ => Some((i as u64, x.parse().unwrap())),
-        })
-        .collect();
-    (arrival, times)
+        .ok_or_else(|| ParseError::new(13, 2, 1, "", "expected a list of bus ids"))?;
+    let mut times = Vec::new();
+    for (i, x) in line.text.split(',').map(str::trim).enumerate() {
+        match x {
+            "x" => {}
+            _ => match line.parse(x, "a bus id or `x`")? {
+                0 => return Err(line.error(x, "expected a non-zero bus id")),
+                id => times.push((i as u64, id)),
+            },
+        }
+    }
+    if times.is_empty() {
+        return Err(line.error(line.text, "expected at least one bus id"));
+    }
+    if let Some(extra) = lines.next() {
+        return Err(extra.error(extra.text, "expected only two lines"));
+    }
+    Ok((arrival, times))
 }
 
 #[aoc(day13, part1)]
-pub fn solve_part1(input: &(u64, Vec<(u64, u64)>)) -> u64 {
-    let (wait, n) = input
-        .1
-        .iter()
-        .map(|(_, x)| (x - (input.0 % x), x))
-        .min()
-        .unwrap();
-
-    wait * n
+pub fn solve_part1(input: &(u64, Vec<(u64, u64)>)) -> Option<u64> {
+    let (wait, n) = input.1.iter().map(|(_, x)| (x - (input.0 % x), x)).min()?;
+
+    Some(wait * n)
 }
 
 #[aoc(day13, part2)]
-pub fn solve_part2(input: &(u64, Vec<(u64, u64)>)) -> u64 {
-    let mut delta = 1;
-    let mut t = 0;
+pub fn solve_part2(input: &(u64, Vec<(u64, u64)>)) -> Option<u64> {
+    let mut delta: u64 = 1;
+    let mut t: u64 = 0;
 
     for (offset, dt) in &input.1 {
-        loop {
-            if (t + *offset) % dt == 0 {
-                break;
-            }
-            t += delta;
-        }
-        delta = delta.lcm(dt);
+        // Stepping by `delta` comes back to the same remainder within `dt` steps, so if
+        // none of those fit this bus, no later time will either.
+        t = (0..*dt)
+            .map_while(|k| delta.checked_mul(k)?.checked_add(t))
+            .find(|t| (t % dt + offset % dt) % dt == 0)?;
+        delta = delta.checked_mul(dt / delta.gcd(dt))?;
+    }
+    Some(t)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = (u64, Vec<(u64, u64)>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
     }
-    t
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use itertools::join;
+    use proptest::prelude::*;
 
     const INPUT: &[&str] = &["939", "7,13,x,x,59,x,31,19"];
 
     #[test]
     fn solve_part1_test() {
         assert_eq!(
-            solve_part1(&input_generator(join(INPUT, "\n").as_str())),
-            295
+            solve_part1(&input_generator(join(INPUT, "\n").as_str()).unwrap()),
+            Some(295)
         );
     }
 
     #[test]
     fn solve_part2_test() {
         assert_eq!(
-            solve_part2(&input_generator(join(INPUT, "\n").as_str())),
-            1068781
+            solve_part2(&input_generator(join(INPUT, "\n").as_str()).unwrap()),
+            Some(1068781)
         );
+        assert_eq!(solve_part2(&(0, vec![(0, 2), (1, 4)])), None);
+    }
+
+    #[test]
+    fn input_generator_test() {
+        let err = input_generator("939\n7,13,x,0").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 8, "0"));
+
+        let err = input_generator("939\n7,13,y").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 6, "y"));
+
+        assert!(input_generator("939").is_err());
+        assert!(input_generator("939\nx,x").is_err());
+    }
+
+    const PRIMES: [u64; 6] = [2, 3, 5, 7, 11, 13];
+
+    proptest! {
+        #[test]
+        fn solve_part2_matches_naive_search(
+            ids in Just(PRIMES.to_vec()).prop_shuffle(),
+            gaps in prop::collection::vec(1u64..4, 1..=PRIMES.len()),
+        ) {
+            let mut offset = 0;
+            let buses: Vec<(u64, u64)> = gaps
+                .iter()
+                .zip(&ids)
+                .map(|(gap, &id)| {
+                    let bus = (offset, id);
+                    offset += gap;
+                    bus
+                })
+                .collect();
+            let expected = (0..)
+                .find(|t| buses.iter().all(|(offset, id)| (t + offset) % id == 0))
+                .unwrap();
+            prop_assert_eq!(solve_part2(&(0, buses)), Some(expected));
+        }
     }
 }
@@ -1,8 +1,16 @@
+use crate::input::lines;
+use crate::solution::Solution;
+use crate::{Answer, Error, ParseError, Unsolved};
 use aoc_runner_derive::aoc;
 use std::collections::HashMap;
 
-#[derive(Debug, Copy, Clone)]
-struct Mask {
+/// The most addresses a part 2 program may write, counting every address each write's
+/// floating mask expands to, so a mask of all `X`s is refused instead of exhausting
+/// memory.
+pub const MAX_PART2_WRITES: u64 = 1 << 24;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Mask {
     or_pattern: u64,
     and_pattern: u64,
 }
@@ -17,28 +25,40 @@ impl Default for Mask {
 }
 
 impl Mask {
-    fn apply(&self, value: u64) -> u64 {
+    pub fn new(or_pattern: u64, and_pattern: u64) -> Self {
+        Mask {
+            or_pattern,
+            and_pattern,
+        }
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
         value & self.and_pattern | self.or_pattern
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct FloatMask {
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct FloatMask {
     overwrite_pattern: u64,
     float_pattern: u64,
 }
 
-impl Default for FloatMask {
-    fn default() -> Self {
+impl FloatMask {
+    pub fn new(overwrite_pattern: u64, float_pattern: u64) -> Self {
         FloatMask {
-            overwrite_pattern: 0,
-            float_pattern: 0,
+            overwrite_pattern,
+            float_pattern,
         }
     }
-}
 
-impl FloatMask {
-    fn apply(&self, location: u64) -> Vec<u64> {
+    /// How many addresses [`apply`](Self::apply) returns, or `None` if that doesn't fit
+    /// in a `u64`.
+    pub fn address_count(&self) -> Option<u64> {
+        1u64.checked_shl(self.float_pattern.count_ones())
+    }
+
+    /// Every memory address `location` decodes to, with floating bits taking both values.
+    pub fn apply(&self, location: u64) -> Vec<u64> {
         let mut result = Vec::with_capacity(1 << self.float_pattern.count_ones());
         let loc = location & !self.float_pattern | self.overwrite_pattern;
         result.push(loc);
@@ -56,6 +76,7 @@ impl FloatMask {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn simple_float_mask_test() {
@@ -74,99 +95,212 @@ mod test {
         };
         assert_eq!(mask.apply(0b000), [0b000, 0b001, 0b100, 0b101]);
     }
+
+    #[test]
+    fn solve_part1_test() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0";
+        assert_eq!(solve_part1(input), Ok(165));
+    }
+
+    #[test]
+    fn solve_part2_test() {
+        let input = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1";
+        assert_eq!(solve_part2(input), Ok(208));
+
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX\nmem[8] = 1";
+        assert_eq!(
+            solve_part2(input),
+            Err(Error::NoSolution { day: 14, part: 2 })
+        );
+        assert_eq!(FloatMask::new(0, u64::MAX).address_count(), None);
+    }
+
+    #[test]
+    fn parse_program_test() {
+        let err = parse_part1("mask = XX1\nmem[8] = 11").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 8, "XX1"));
+
+        let err = parse_part2("mem[8] = 11\nmem[x] = 1").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "x"));
+
+        let err = parse_part2("mem[8] = 11\nmem 8 = 1").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "mem 8"));
+
+        assert!(parse_part1("mem[8]").is_err());
+        assert!(parse_part1("mem[8] = -1").is_err());
+    }
+
+    /// Every address the mask can produce, built one bit at a time for each assignment
+    /// of the floating bits.
+    fn float_mask_oracle(mask: &FloatMask, location: u64) -> Vec<u64> {
+        let floating: Vec<u32> = (0..64)
+            .filter(|bit| (mask.float_pattern >> bit) & 1 == 1)
+            .collect();
+        let mut addresses: Vec<u64> = (0..1u64 << floating.len())
+            .map(|choice| {
+                (0..64).fold(0, |address, bit| {
+                    let value = match floating.iter().position(|&f| f == bit) {
+                        Some(i) => (choice >> i) & 1,
+                        None if (mask.overwrite_pattern >> bit) & 1 == 1 => 1,
+                        None => (location >> bit) & 1,
+                    };
+                    address | value << bit
+                })
+            })
+            .collect();
+        addresses.sort_unstable();
+        addresses
+    }
+
+    proptest! {
+        #[test]
+        fn float_mask_matches_bit_enumeration(
+            floating in prop::collection::btree_set(0u32..36, 0..=8),
+            overwrite in 0u64..1 << 36,
+            location in 0u64..1 << 36,
+        ) {
+            let float_pattern = floating.iter().fold(0, |p, bit| p | 1 << bit);
+            let mask = FloatMask::new(overwrite & !float_pattern, float_pattern);
+            let mut addresses = mask.apply(location);
+            addresses.sort_unstable();
+            prop_assert_eq!(addresses, float_mask_oracle(&mask, location));
+        }
+    }
 }
 
-#[derive(Debug)]
-enum Instruction {
-    Mask(Mask),
-    FloatMask(FloatMask),
+/// A program instruction, where `M` is the mask type the program is decoded with:
+/// [`Mask`] for part 1 and [`FloatMask`] for part 2.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Instruction<M> {
+    Mask(M),
     Memory(u64, u64),
 }
 
-#[aoc(day14, part1)]
-fn solve_part1(input: &str) -> u64 {
-    let instructions: Vec<Instruction> = input
-        .lines()
-        .map(|l| {
-            let words = l.split(' ').collect::<Vec<_>>();
-            if words[0] == "mask" {
-                let mask_str = String::from(words[2]);
-                let and_pattern = mask_str.replace('X', "1");
-                let and_pattern = u64::from_str_radix(&and_pattern, 2).unwrap();
-                let or_pattern = mask_str.replace('X', "0");
-                let or_pattern = u64::from_str_radix(&or_pattern, 2).unwrap();
-                let mask = Mask {
-                    or_pattern,
-                    and_pattern,
-                };
-                Instruction::Mask(mask)
-            } else if words[0].starts_with("mem[") {
-                let location = String::from(&words[0][4..(words[0].len() - 1)]);
-                let location: u64 = location.parse().unwrap();
-                let value: u64 = words[2].parse().unwrap();
-                Instruction::Memory(location, value)
+/// Parses the initialization program, building each bitmask with `mask`.
+fn parse_program<M, F>(input: &str, mask: F) -> Result<Vec<Instruction<M>>, ParseError>
+where
+    F: Fn(&str) -> M,
+{
+    lines(14, input)
+        .map(|line| {
+            let (target, value) = line
+                .text
+                .split_once('=')
+                .map(|(target, value)| (target.trim(), value.trim()))
+                .ok_or_else(|| line.error(line.text, "expected `<target> = <value>`"))?;
+            if target == "mask" {
+                if value.len() != 36 || value.contains(|c| !matches!(c, 'X' | '0' | '1')) {
+                    return Err(line.error(value, "expected 36 characters of `X`, `0` or `1`"));
+                }
+                Ok(Instruction::Mask(mask(value)))
+            } else if let Some(location) = target
+                .strip_prefix("mem[")
+                .and_then(|t| t.strip_suffix(']'))
+            {
+                Ok(Instruction::Memory(
+                    line.parse(location, "a memory address")?,
+                    line.parse(value, "an unsigned value")?,
+                ))
             } else {
-                unreachable!()
+                Err(line.error(target, "expected `mask` or `mem[<address>]`"))
             }
         })
-        .collect();
+        .collect()
+}
+
+/// Bits of `mask` that are set to `c`, most significant first.
+fn bits(mask: &str, c: char) -> u64 {
+    mask.chars().fold(0, |acc, ch| acc << 1 | (ch == c) as u64)
+}
+
+pub fn parse_part1(input: &str) -> Result<Vec<Instruction<Mask>>, ParseError> {
+    parse_program(input, |mask| Mask {
+        or_pattern: bits(mask, '1'),
+        and_pattern: bits(mask, 'X') | bits(mask, '1'),
+    })
+}
 
+pub fn parse_part2(input: &str) -> Result<Vec<Instruction<FloatMask>>, ParseError> {
+    parse_program(input, |mask| FloatMask {
+        overwrite_pattern: bits(mask, '1'),
+        float_pattern: bits(mask, 'X'),
+    })
+}
+
+#[aoc(day14, part1)]
+pub fn solve_part1(input: &str) -> Result<u64, ParseError> {
+    Ok(run_part1(&parse_part1(input)?))
+}
+
+/// Runs a program decoded with value masks, returning the sum of memory.
+pub fn run_part1(instructions: &[Instruction<Mask>]) -> u64 {
     let mut mem = HashMap::new();
     let mut current_mask = Mask::default();
-    for instr in instructions {
+    for &instr in instructions {
         match instr {
             Instruction::Mask(mask) => current_mask = mask,
             Instruction::Memory(location, value) => {
                 mem.insert(location, current_mask.apply(value));
             }
-            _ => unreachable!(),
         }
     }
     mem.values().sum()
 }
 
 #[aoc(day14, part2)]
-fn solve_part2(input: &str) -> u64 {
-    let instructions: Vec<Instruction> = input
-        .lines()
-        .map(|l| {
-            let words = l.split(' ').collect::<Vec<_>>();
-            if words[0] == "mask" {
-                let mask_str = String::from(words[2]);
-                let overwrite_pattern = mask_str.replace('X', "0");
-                let overwrite_pattern = u64::from_str_radix(&overwrite_pattern, 2).unwrap();
-                let float_pattern = mask_str.replace('1', "0").replace('X', "1");
-                let float_pattern = u64::from_str_radix(&float_pattern, 2).unwrap();
-                let mask = FloatMask {
-                    overwrite_pattern,
-                    float_pattern,
-                };
-                Instruction::FloatMask(mask)
-            } else if words[0].starts_with("mem[") {
-                let location = String::from(&words[0][4..(words[0].len() - 1)]);
-                let location: u64 = location.parse().unwrap();
-                let value: u64 = words[2].parse().unwrap();
-                Instruction::Memory(location, value)
-            } else {
-                unreachable!()
-            }
-        })
-        .collect();
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
+    run_part2(&parse_part2(input)?).ok_or(Error::NoSolution { day: 14, part: 2 })
+}
 
+/// Runs a program decoded with address masks, returning the sum of memory, or `None` if
+/// it writes more than [`MAX_PART2_WRITES`] addresses.
+pub fn run_part2(instructions: &[Instruction<FloatMask>]) -> Option<u64> {
     let mut mem: HashMap<u64, u64> = HashMap::new();
     let mut current_mask = FloatMask::default();
-    for instr in instructions {
+    let mut writes: u64 = 0;
+    for &instr in instructions {
         match instr {
-            Instruction::FloatMask(mask) => {
+            Instruction::Mask(mask) => {
                 current_mask = mask;
             }
             Instruction::Memory(location, value) => {
+                writes = writes.checked_add(current_mask.address_count()?)?;
+                if writes > MAX_PART2_WRITES {
+                    return None;
+                }
                 for loc in current_mask.apply(location) {
                     mem.insert(loc, value);
                 }
             }
-            _ => unreachable!(),
         }
     }
-    mem.values().sum()
+    Some(mem.values().sum())
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = (Vec<Instruction<Mask>>, Vec<Instruction<FloatMask>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((parse_part1(input)?, parse_part2(input)?))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(run_part1(&input.0).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        run_part2(&input.1)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
 }
@@ -1,22 +1,58 @@
+use crate::input::lines;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::{HashMap, VecDeque};
 
 #[aoc_generator(day15)]
-fn parse_input(input: &str) -> Vec<u32> {
-    input.split(',').map(|w| w.parse().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut lines = lines(15, input);
+    let line = lines.next().ok_or_else(|| ParseError::empty(15))?;
+    if let Some(extra) = lines.next() {
+        return Err(extra.error(extra.text, "expected a single line of numbers"));
+    }
+    line.text
+        .split(',')
+        .map(|w| line.parse(w.trim(), "a starting number"))
+        .collect()
 }
 
 #[aoc(day15, part1)]
-fn solve_part1(input: &[u32]) -> u32 {
-    run(&input, 2020)
+pub fn solve_part1(input: &[u32]) -> Option<u32> {
+    run(input, 2020)
 }
 
 #[aoc(day15, part2)]
-fn solve_part2(input: &[u32]) -> u32 {
-    run(&input, 30000000)
+pub fn solve_part2(input: &[u32]) -> Option<u32> {
+    run(input, 30000000)
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
 }
 
-fn run(input: &[u32], turn_count: u32) -> u32 {
+/// The number spoken on turn `turn_count`, or `None` if there are no starting numbers.
+pub fn run(input: &[u32], turn_count: u32) -> Option<u32> {
     let mut spoken: HashMap<u32, VecDeque<u32>> = HashMap::new();
     let mut last_spoken = 0;
 
@@ -25,20 +61,17 @@ fn run(input: &[u32], turn_count: u32) -> u32 {
     }
 
     for turn in input.len() as u32 + 1..=turn_count {
-        if let Some(v) = spoken.get_mut(&last_spoken) {
-            if v.len() == 1 {
-                speak_number(&mut spoken, &mut last_spoken, turn, 0);
-            } else {
-                let before_last = v.pop_front().unwrap();
-                let diff = v[v.len() - 1] - before_last;
-                speak_number(&mut spoken, &mut last_spoken, turn, diff);
-            }
+        let v = spoken.get_mut(&last_spoken)?;
+        if v.len() == 1 {
+            speak_number(&mut spoken, &mut last_spoken, turn, 0);
         } else {
-            unreachable!();
+            let before_last = v.pop_front()?;
+            let diff = v[v.len() - 1] - before_last;
+            speak_number(&mut spoken, &mut last_spoken, turn, diff);
         }
     }
 
-    last_spoken
+    Some(last_spoken)
 }
 
 #[inline(always)]
@@ -48,10 +81,7 @@ fn speak_number(
     turn: u32,
     n: u32,
 ) {
-    spoken
-        .entry(n)
-        .or_insert_with(VecDeque::new)
-        .push_back(turn);
+    spoken.entry(n).or_default().push_back(turn);
     *last_spoken = n;
 }
 
@@ -62,7 +92,18 @@ mod test {
     #[test]
     fn run_test() {
         let input: &[u32] = &[0, 3, 6];
-        assert_eq!(run(input, 4), 0);
-        assert_eq!(run(input, 2020), 436);
+        assert_eq!(run(input, 4), Some(0));
+        assert_eq!(run(input, 2020), Some(436));
+        assert_eq!(run(&[], 2020), None);
+    }
+
+    #[test]
+    fn input_generator_test() {
+        assert_eq!(input_generator("0,3,6"), Ok(vec![0, 3, 6]));
+
+        let err = input_generator("0,3,,6").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 5, ""));
+
+        assert_eq!(input_generator("").unwrap_err(), ParseError::empty(15));
     }
 }
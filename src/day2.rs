@@ -1,54 +1,182 @@
+use crate::input::{lines, Line};
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
+use policy::{CountPolicy, PasswordPolicy, PositionPolicy};
+use std::fmt;
+use std::str::FromStr;
+
+pub mod audit;
+pub mod generate;
+pub mod policy;
+pub mod record;
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<Password> {
-    input
-        .lines()
-        .map(|l| {
-            let mut parts = l.split(' ');
-            let limits = parts.next().unwrap();
-            let mut limits = limits.split('-').map(|s| s.parse().unwrap());
-            Password {
-                limits: (limits.next().unwrap(), limits.next().unwrap()),
-                letter: String::from(parts.next().unwrap()).chars().next().unwrap(),
-                password: String::from(parts.next().unwrap()),
-            }
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Password>, ParseError> {
+    lines(2, input).map(parse_password).collect()
+}
+
+fn parse_password(line: Line) -> Result<Password, ParseError> {
+    parse_fields(&line, line.text)
+}
+
+/// Parses `text`, a slice of `line`, as `<min>-<max> <letter>: <password>`.
+fn parse_fields(line: &Line, text: &str) -> Result<Password, ParseError> {
+    let mut parts = text.split_whitespace();
+    let (limits, letter, password) = match (parts.next(), parts.next(), parts.next(), parts.next())
+    {
+        (Some(limits), Some(letter), Some(password), None) => (limits, letter, password),
+        _ => {
+            return Err(line.error(text, "expected `<min>-<max> <letter>: <password>`"));
+        }
+    };
+    let limits = match limits.split_once('-') {
+        Some((low, high)) => (
+            line.parse(low, "a lower limit")?,
+            line.parse(high, "an upper limit")?,
+        ),
+        None => return Err(line.error(limits, "expected `<min>-<max>`")),
+    };
+    let mut chars = letter.chars();
+    let letter = match (chars.next(), chars.next(), chars.next()) {
+        (Some(letter), Some(':'), None) => letter,
+        _ => return Err(line.error(letter, "expected a single letter followed by `:`")),
+    };
+    Ok(Password {
+        limits,
+        letter,
+        password: String::from(password),
+    })
 }
 
 #[aoc(day2, part1)]
 pub fn solve_part1(input: &[Password]) -> usize {
-    input.iter().filter(|&p| is_password_valid_part1(p)).count()
+    count_valid(input, &CountPolicy)
 }
 
 #[aoc(day2, part2)]
 pub fn solve_part2(input: &[Password]) -> usize {
-    input.iter().filter(|&p| is_password_valid_part2(p)).count()
+    count_valid(input, &PositionPolicy)
+}
+
+/// The only non-blank line of `input`.
+fn single_line(input: &str) -> Result<Line<'_>, ParseError> {
+    let mut lines = lines(2, input);
+    match (lines.next(), lines.next()) {
+        (Some(line), None) => Ok(line),
+        (Some(_), Some(extra)) => Err(extra.error(extra.text, "expected a single record")),
+        (None, _) => Err(ParseError::empty(2)),
+    }
 }
 
+/// How many of `passwords` satisfy `policy`.
+pub fn count_valid(passwords: &[Password], policy: &dyn PasswordPolicy) -> usize {
+    passwords.iter().filter(|&p| policy.is_valid(p)).count()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Password>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part2(input).into())
+    }
+}
+
+/// One record of the corpus: a policy line and the password it applies to.
+///
+/// Displays as `<min>-<max> <letter>: <password>`, which parses back to an equal
+/// record whenever the letter isn't whitespace and the password is non-empty and has
+/// no whitespace, as is true of every record that was parsed.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Password {
     limits: (usize, usize),
     letter: char,
     password: String,
 }
 
-fn is_password_valid_part1(pass: &Password) -> bool {
-    let count = pass.password.matches(pass.letter).count();
-    count >= pass.limits.0 && count <= pass.limits.1
+impl Password {
+    pub fn new(limits: (usize, usize), letter: char, password: impl Into<String>) -> Self {
+        Password {
+            limits,
+            letter,
+            password: password.into(),
+        }
+    }
+
+    pub fn limits(&self) -> (usize, usize) {
+        self.limits
+    }
+
+    pub fn letter(&self) -> char {
+        self.letter
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// Whether the letter is at 1-based `position`, counting `char`s. Position 0 and
+    /// positions past the end hold no letter.
+    pub fn letter_at(&self, position: usize) -> bool {
+        position
+            .checked_sub(1)
+            .and_then(|i| self.password.chars().nth(i))
+            == Some(self.letter)
+    }
 }
 
-fn is_password_valid_part2(pass: &Password) -> bool {
-    let (letter1, letter2) = (
-        pass.password.chars().nth(pass.limits.0 - 1).unwrap(),
-        pass.password.chars().nth(pass.limits.1 - 1).unwrap(),
-    );
-    (letter1 == pass.letter) ^ (letter2 == pass.letter)
+impl FromStr for Password {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        parse_password(single_line(s)?)
+    }
+}
+
+impl fmt::Display for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.limits;
+        write!(f, "{}-{} {}: {}", min, max, self.letter, self.password)
+    }
+}
+
+pub fn is_password_valid_part1(pass: &Password) -> bool {
+    CountPolicy.is_valid(pass)
+}
+
+pub fn is_password_valid_part2(pass: &Password) -> bool {
+    PositionPolicy.is_valid(pass)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
+
+    /// Records that survive a trip through their line format.
+    pub(super) fn password_strategy() -> impl Strategy<Value = Password> {
+        let word = any::<char>().prop_filter("whitespace", |c| !c.is_whitespace());
+        (
+            (0usize..100, 0usize..100),
+            word.clone(),
+            prop::collection::vec(word, 1..12),
+        )
+            .prop_map(|(limits, letter, password)| {
+                Password::new(limits, letter, password.into_iter().collect::<String>())
+            })
+    }
 
     #[test]
     fn is_password_valid_part1_test() {
@@ -110,4 +238,56 @@ mod test {
         };
         assert!(!is_password_valid_part2(&p));
     }
+
+    #[test]
+    fn input_generator_test() {
+        let passwords = input_generator("1-3 a: abcde\n2-9 c: ccccccccc").unwrap();
+        assert_eq!(passwords.len(), 2);
+        assert_eq!(passwords[1].limits, (2, 9));
+        assert_eq!(passwords[1].letter, 'c');
+
+        let err = input_generator("1-3 a: abcde\n1-x b: cdefg").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
+
+        let err = input_generator("1-3 ab: abcde").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 5, "ab:"));
+
+        assert!(input_generator("1-3 a:").is_err());
+
+        let passwords = input_generator("1-2 é: éa\n2-3 日: 本日日").unwrap();
+        assert_eq!((passwords[0].letter, passwords[1].letter), ('é', '日'));
+        assert_eq!(solve_part2(&passwords), 1);
+
+        let err = input_generator("1-2 é: éa\n1-2 éé: a").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "éé:"));
+    }
+
+    #[test]
+    fn count_valid_test() {
+        let passwords = input_generator("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+        let policy = policy::parse("count & !require:digit").unwrap();
+        assert_eq!(count_valid(&passwords, policy.as_ref()), 2);
+        let policy = policy::parse("position | min-length:6").unwrap();
+        assert_eq!(count_valid(&passwords, policy.as_ref()), 2);
+    }
+
+    #[test]
+    fn from_str_test() {
+        let pass: Password = "  1-3 a: abcde\n".parse().unwrap();
+        assert_eq!(pass, Password::new((1, 3), 'a', "abcde"));
+        assert_eq!(pass.to_string(), "1-3 a: abcde");
+
+        let err = "1-3 a: abcde\n2-9 c: ccccccccc"
+            .parse::<Password>()
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!("\n".parse::<Password>().unwrap_err(), ParseError::empty(2));
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trips(pass in password_strategy()) {
+            prop_assert_eq!(pass.to_string().parse::<Password>().unwrap(), pass);
+        }
+    }
 }
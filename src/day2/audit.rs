@@ -0,0 +1,145 @@
+//! Per-record audits of a password corpus against a policy, exportable as CSV or JSON.
+
+use super::policy::{PasswordPolicy, Violation};
+use super::record::Record;
+use super::Password;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// A record that broke the audited policy.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct Finding {
+    /// Position of the record in the corpus, counting from 1.
+    pub record: usize,
+    pub min: usize,
+    pub max: usize,
+    pub letter: char,
+    pub password: String,
+    /// The policy that failed, as a spec.
+    pub policy: String,
+    pub reason: String,
+}
+
+/// Checks every record against `policy`, returning a finding for each one that fails.
+pub fn audit(passwords: &[Password], policy: &dyn PasswordPolicy) -> Vec<Finding> {
+    findings(passwords.iter().map(|pass| (pass, policy.violation(pass))))
+}
+
+/// Checks every record against the policy it names, returning a finding for each one
+/// that fails.
+pub fn audit_records(records: &[Record]) -> Vec<Finding> {
+    findings(records.iter().map(|r| (&r.password, r.violation())))
+}
+
+fn findings<'a>(checked: impl Iterator<Item = (&'a Password, Option<Violation>)>) -> Vec<Finding> {
+    checked
+        .enumerate()
+        .filter_map(|(i, (pass, violation))| {
+            let violation = violation?;
+            Some(Finding {
+                record: i + 1,
+                min: pass.limits.0,
+                max: pass.limits.1,
+                letter: pass.letter,
+                password: pass.password.clone(),
+                policy: violation.policy,
+                reason: violation.reason,
+            })
+        })
+        .collect()
+}
+
+/// Findings as CSV with a header row, quoting fields as RFC 4180 requires.
+pub fn to_csv(findings: &[Finding]) -> String {
+    let mut csv = String::from("record,min,max,letter,password,policy,reason\n");
+    for f in findings {
+        let fields = [
+            f.record.to_string(),
+            f.min.to_string(),
+            f.max.to_string(),
+            f.letter.to_string(),
+            f.password.clone(),
+            f.policy.clone(),
+            f.reason.clone(),
+        ];
+        let fields: Vec<_> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Findings as a JSON array of objects.
+pub fn to_json(findings: &[Finding]) -> String {
+    serde_json::to_string_pretty(findings).expect("findings always serialize")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::{input_generator, policy, record};
+
+    #[test]
+    fn audit_test() {
+        let passwords = input_generator("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+        let findings = audit(&passwords, &policy::PositionPolicy);
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|f| (f.record, f.reason.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, "neither position 1 nor 3 matches"),
+                (3, "positions 2 and 9 both match"),
+            ]
+        );
+
+        let policy = policy::parse("count & forbid:,\"").unwrap();
+        let passwords = input_generator("1-3 a: a,b\n1-3 a: abc").unwrap();
+        assert_eq!(
+            to_csv(&audit(&passwords, policy.as_ref())),
+            "record,min,max,letter,password,policy,reason\n\
+             1,1,3,a,\"a,b\",\"forbid:,\"\"\",\"contains forbidden character ','\"\n"
+        );
+    }
+
+    #[test]
+    fn audit_records_test() {
+        let records =
+            record::parse_records("count 1-3 a: abcde\nposition 1-3 b: cdefg\ncount 2-9 c: ccc")
+                .unwrap();
+        let summary: Vec<_> = audit_records(&records)
+            .into_iter()
+            .map(|f| (f.record, f.policy))
+            .collect();
+        assert_eq!(summary, vec![(2, String::from("position"))]);
+    }
+
+    #[test]
+    fn to_json_test() {
+        let passwords = input_generator("1-2 p: pppppppp").unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(&audit(&passwords, &policy::CountPolicy))).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "record": 1,
+                "min": 1,
+                "max": 2,
+                "letter": "p",
+                "password": "pppppppp",
+                "policy": "count",
+                "reason": "letter 'p' appears 8 times, allowed 1-2",
+            }])
+        );
+    }
+}
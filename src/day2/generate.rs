@@ -0,0 +1,265 @@
+//! Builds passwords that satisfy or deliberately break a line's count or position rule,
+//! for test corpora and for round-trip testing the validators.
+
+use super::policy::PolicyKind;
+use super::Password;
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::iter;
+use std::ops::Range;
+
+/// The most characters a random password gets beyond what its rule needs.
+const MAX_PADDING: usize = 8;
+
+/// The most characters a random password's rule may need, so that huge limits are
+/// refused instead of allocated.
+const MAX_LENGTH: usize = 1 << 16;
+
+/// Generates passwords for one policy line: its limits, its letter and how they're read.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    limits: (usize, usize),
+    letter: char,
+    kind: PolicyKind,
+    /// Characters other than the letter to fill passwords with.
+    filler: Vec<char>,
+}
+
+impl Generator {
+    /// A generator that fills passwords with lowercase ASCII letters.
+    pub fn new(limits: (usize, usize), letter: char, kind: PolicyKind) -> Self {
+        Generator {
+            limits,
+            letter,
+            kind,
+            filler: Vec::new(),
+        }
+        .with_alphabet("abcdefghijklmnopqrstuvwxyz")
+    }
+
+    /// A generator for the policy line of an existing record.
+    pub fn for_password(pass: &Password, kind: PolicyKind) -> Self {
+        Generator::new(pass.limits, pass.letter, kind)
+    }
+
+    /// Fills passwords with characters from `alphabet` instead, ignoring the letter.
+    ///
+    /// Panics if `alphabet` has no character other than the letter.
+    pub fn with_alphabet(mut self, alphabet: &str) -> Self {
+        let letter = self.letter;
+        self.filler = alphabet.chars().filter(|&c| c != letter).unique().collect();
+        assert!(
+            !self.filler.is_empty(),
+            "alphabet needs a character other than the letter"
+        );
+        self
+    }
+
+    fn password(&self, chars: impl IntoIterator<Item = char>) -> Password {
+        Password::new(
+            self.limits,
+            self.letter,
+            chars.into_iter().collect::<String>(),
+        )
+    }
+
+    /// A random password that satisfies the rule if `valid` is set and breaks it if not,
+    /// or `None` if no password can, such as a valid one for the position rule when both
+    /// positions are the same, or if every such password has over 65,536 characters.
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R, valid: bool) -> Option<Password> {
+        let chars = match self.kind {
+            PolicyKind::Count => self.random_count(rng, valid)?,
+            PolicyKind::Position => self.random_position(rng, valid)?,
+        };
+        Some(self.password(chars))
+    }
+
+    fn random_count<R: Rng + ?Sized>(&self, rng: &mut R, valid: bool) -> Option<Vec<char>> {
+        let (min, max) = self.limits;
+        // Counts of the letter that give the wanted outcome, kept near the limits.
+        let counts: [Range<usize>; 2] = if valid {
+            [
+                min..max.min(min.saturating_add(MAX_PADDING)).saturating_add(1),
+                0..0,
+            ]
+        } else {
+            [
+                min.saturating_sub(MAX_PADDING)..min,
+                max.saturating_add(1)..max.saturating_add(1 + MAX_PADDING),
+            ]
+        };
+        let counts: Vec<_> = counts
+            .iter()
+            .filter(|r| !r.is_empty() && r.start <= MAX_LENGTH)
+            .cloned()
+            .collect();
+        let range = counts.choose(rng)?.clone();
+        let count = rng.gen_range(range);
+
+        let padding = rng.gen_range(0..=MAX_PADDING);
+        let mut chars: Vec<char> = iter::repeat_n(self.letter, count)
+            .chain((0..padding).map(|_| *self.filler.choose(rng).unwrap()))
+            .collect();
+        chars.shuffle(rng);
+        Some(chars)
+    }
+
+    fn random_position<R: Rng + ?Sized>(&self, rng: &mut R, valid: bool) -> Option<Vec<char>> {
+        let (first, second) = self.limits;
+        // Which 1-based positions must hold the letter (`true`) or must not (`false`).
+        let options: Vec<[(usize, bool); 2]> = if valid {
+            vec![
+                [(first, true), (second, false)],
+                [(second, true), (first, false)],
+            ]
+        } else {
+            vec![
+                [(first, true), (second, true)],
+                [(first, false), (second, false)],
+            ]
+        };
+        let feasible = |option: &[(usize, bool); 2]| {
+            option
+                .iter()
+                .all(|&(pos, letter)| !letter || (1..=MAX_LENGTH).contains(&pos))
+                && (option[0].0 != option[1].0 || option[0].1 == option[1].1)
+        };
+        let options: Vec<_> = options.into_iter().filter(feasible).collect();
+        let option = options.choose(rng)?;
+
+        // A position past the end never holds the letter, so only positions that must
+        // hold it have to fit, though others are included when they're short enough.
+        let len = option
+            .iter()
+            .filter(|&&(pos, letter)| letter || pos <= MAX_LENGTH)
+            .map(|&(pos, _)| pos)
+            .max()
+            .unwrap_or(0)
+            + rng.gen_range(0..=MAX_PADDING);
+        let mut chars: Vec<char> = (0..len)
+            .map(|_| match rng.gen_range(0..=self.filler.len()) {
+                0 => self.letter,
+                i => self.filler[i - 1],
+            })
+            .collect();
+        for &(pos, letter) in option.iter().filter(|&&(pos, _)| pos > 0 && pos <= len) {
+            chars[pos - 1] = if letter {
+                self.letter
+            } else {
+                *self.filler.choose(rng).unwrap()
+            };
+        }
+        Some(chars)
+    }
+
+    /// Every password of `len` characters, drawn from the letter and the alphabet, that
+    /// satisfies the rule if `valid` is set and breaks it if not. Passwords come in
+    /// order, with the letter sorting before the alphabet in the order it was given.
+    pub fn enumerate(&self, len: usize, valid: bool) -> impl Iterator<Item = Password> + '_ {
+        let chars: Vec<char> = iter::once(self.letter)
+            .chain(self.filler.iter().copied())
+            .collect();
+        let strings: Box<dyn Iterator<Item = Vec<char>>> = match len {
+            0 => Box::new(iter::once(Vec::new())),
+            _ => Box::new(
+                (0..len)
+                    .map(|_| chars.clone().into_iter())
+                    .multi_cartesian_product(),
+            ),
+        };
+        let policy = self.kind.policy();
+        strings
+            .map(move |chars| self.password(chars))
+            .filter(move |pass| policy.is_valid(pass) == valid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn enumerate_test() {
+        let generator = Generator::new((1, 1), 'a', PolicyKind::Count).with_alphabet("ba");
+        let valid: Vec<String> = generator
+            .enumerate(2, true)
+            .map(|p| p.password().to_string())
+            .collect();
+        assert_eq!(valid, vec!["ab", "ba"]);
+        assert_eq!(generator.enumerate(2, false).count(), 2);
+        assert_eq!(generator.enumerate(0, false).count(), 1);
+
+        let generator = Generator::new((1, 3), 'é', PolicyKind::Position).with_alphabet("x");
+        let valid: Vec<String> = generator
+            .enumerate(3, true)
+            .map(|p| p.password().to_string())
+            .collect();
+        assert_eq!(valid, vec!["ééx", "éxx", "xéé", "xxé"]);
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = StdRng::seed_from_u64(2020);
+        let generator = Generator::new((1, 3), 'a', PolicyKind::Count);
+        for _ in 0..100 {
+            let pass = generator.random(&mut rng, true).unwrap();
+            assert_eq!((pass.limits(), pass.letter()), ((1, 3), 'a'));
+            assert!(PolicyKind::Count.policy().is_valid(&pass));
+        }
+
+        assert!(Generator::new((2, 2), 'a', PolicyKind::Position)
+            .random(&mut rng, true)
+            .is_none());
+        assert!(Generator::new((3, 1), 'a', PolicyKind::Count)
+            .random(&mut rng, true)
+            .is_none());
+        assert!(Generator::new((0, usize::MAX), 'a', PolicyKind::Count)
+            .random(&mut rng, false)
+            .is_none());
+    }
+
+    #[test]
+    fn random_huge_limits_test() {
+        let mut rng = StdRng::seed_from_u64(2020);
+        for valid in [true, false] {
+            let pass = Generator::new((1, usize::MAX), 'a', PolicyKind::Position)
+                .random(&mut rng, valid)
+                .unwrap();
+            assert!(pass.password().chars().count() <= 1 + MAX_PADDING);
+            assert_eq!(PolicyKind::Position.policy().is_valid(&pass), valid);
+        }
+
+        let huge = (usize::MAX - 1, usize::MAX);
+        assert!(Generator::new(huge, 'a', PolicyKind::Position)
+            .random(&mut rng, true)
+            .is_none());
+        assert!(Generator::new(huge, 'a', PolicyKind::Count)
+            .random(&mut rng, true)
+            .is_none());
+        assert!(Generator::new(huge, 'a', PolicyKind::Count)
+            .random(&mut rng, false)
+            .is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn random_matches_rule(
+            limits in (0usize..6, 0usize..6),
+            position in any::<bool>(),
+            valid in any::<bool>(),
+            seed in any::<u64>(),
+        ) {
+            let kind = if position { PolicyKind::Position } else { PolicyKind::Count };
+            let generator = Generator::new(limits, 'a', kind).with_alphabet("b");
+            match generator.random(&mut StdRng::seed_from_u64(seed), valid) {
+                Some(pass) => prop_assert_eq!(kind.policy().is_valid(&pass), valid),
+                // Nothing short enough to enumerate exists either.
+                None => prop_assert!((0..8).all(|len| generator.enumerate(len, valid).next().is_none())),
+            }
+        }
+    }
+}
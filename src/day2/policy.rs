@@ -0,0 +1,695 @@
+//! Password policies, which can be combined and chosen at runtime from a spec such as
+//! `count & !(min-length:12 | require:digit)`.
+//!
+//! Every policy displays as the spec that parses back to it, except an empty [`And`],
+//! [`Or`] or [`Forbidden`], which no spec describes: they display as `()` and `forbid:`,
+//! which the parser rejects.
+
+use super::Password;
+use crate::ParseError;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+/// A rule a password record must satisfy.
+pub trait PasswordPolicy: fmt::Display {
+    /// Why `password` breaks this policy, or `None` if it satisfies it.
+    fn violation(&self, password: &Password) -> Option<Violation>;
+
+    fn is_valid(&self, password: &Password) -> bool {
+        self.violation(password).is_none()
+    }
+}
+
+impl fmt::Debug for dyn PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The policy a password broke, as a spec, and a description of how it broke it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Violation {
+    pub policy: String,
+    pub reason: String,
+}
+
+impl Violation {
+    fn of(policy: &dyn PasswordPolicy, reason: impl Into<String>) -> Self {
+        Violation {
+            policy: policy.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.policy, self.reason)
+    }
+}
+
+/// The letter appears between `min` and `max` times, inclusive (part 1).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct CountPolicy;
+
+impl PasswordPolicy for CountPolicy {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        let count = pass.password.matches(pass.letter).count();
+        if count >= pass.limits.0 && count <= pass.limits.1 {
+            return None;
+        }
+        let reason = format!(
+            "letter '{}' appears {} time{}, allowed {}-{}",
+            pass.letter,
+            count,
+            if count == 1 { "" } else { "s" },
+            pass.limits.0,
+            pass.limits.1
+        );
+        Some(Violation::of(self, reason))
+    }
+}
+
+impl fmt::Display for CountPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("count")
+    }
+}
+
+/// The letter is at exactly one of the two 1-based positions (part 2).
+///
+/// Positions count `char`s, so multi-byte letters take up one position each. A position
+/// of 0 or past the end of the password never matches.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct PositionPolicy;
+
+impl PasswordPolicy for PositionPolicy {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        let (first, second) = pass.limits;
+        let reason = match (pass.letter_at(first), pass.letter_at(second)) {
+            (true, false) | (false, true) => return None,
+            (true, true) => format!("positions {} and {} both match", first, second),
+            (false, false) => format!("neither position {} nor {} matches", first, second),
+        };
+        let len = pass.password.chars().count();
+        let reason = match [first, second].iter().find(|&&p| p == 0 || p > len) {
+            Some(_) if len == 0 => format!("{} (the password is empty)", reason),
+            Some(p) => format!("{} (position {} is outside 1-{})", reason, p, len),
+            None => reason,
+        };
+        Some(Violation::of(self, reason))
+    }
+}
+
+impl fmt::Display for PositionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("position")
+    }
+}
+
+/// The rules a password line's limits and letter can be read under.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PolicyKind {
+    Count,
+    Position,
+}
+
+impl PolicyKind {
+    pub fn policy(self) -> &'static dyn PasswordPolicy {
+        match self {
+            PolicyKind::Count => &CountPolicy,
+            PolicyKind::Position => &PositionPolicy,
+        }
+    }
+}
+
+impl fmt::Display for PolicyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.policy().fmt(f)
+    }
+}
+
+impl FromStr for PolicyKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "count" => Ok(PolicyKind::Count),
+            "position" => Ok(PolicyKind::Position),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The password has at least this many characters.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MinLength(pub usize);
+
+impl PasswordPolicy for MinLength {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        match pass.password.chars().count() {
+            len if len >= self.0 => None,
+            len => Some(Violation::of(
+                self,
+                format!("{} characters, fewer than {}", len, self.0),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MinLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "min-length:{}", self.0)
+    }
+}
+
+/// The password has at most this many characters.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MaxLength(pub usize);
+
+impl PasswordPolicy for MaxLength {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        match pass.password.chars().count() {
+            len if len <= self.0 => None,
+            len => Some(Violation::of(
+                self,
+                format!("{} characters, more than {}", len, self.0),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MaxLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "max-length:{}", self.0)
+    }
+}
+
+/// None of these characters appear in the password.
+///
+/// Displays with a `\` before each whitespace, operator, parenthesis or `\` character, so
+/// any set of characters parses back.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Forbidden(pub String);
+
+impl PasswordPolicy for Forbidden {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        let c = pass.password.chars().find(|&c| self.0.contains(c))?;
+        Some(Violation::of(
+            self,
+            format!("contains forbidden character '{}'", c),
+        ))
+    }
+}
+
+impl fmt::Display for Forbidden {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("forbid:")?;
+        for c in self.0.chars() {
+            if c == '\\' || ends_atom(c) {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+/// A kind of character a password can be required to contain.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CharClass {
+    Lower,
+    Upper,
+    Digit,
+    /// Anything that isn't a letter, a digit or whitespace.
+    Symbol,
+}
+
+impl CharClass {
+    pub fn contains(self, c: char) -> bool {
+        match self {
+            CharClass::Lower => c.is_lowercase(),
+            CharClass::Upper => c.is_uppercase(),
+            CharClass::Digit => c.is_numeric(),
+            CharClass::Symbol => !c.is_alphanumeric() && !c.is_whitespace(),
+        }
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CharClass::Lower => "lower",
+            CharClass::Upper => "upper",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol",
+        })
+    }
+}
+
+impl FromStr for CharClass {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "lower" => Ok(CharClass::Lower),
+            "upper" => Ok(CharClass::Upper),
+            "digit" => Ok(CharClass::Digit),
+            "symbol" => Ok(CharClass::Symbol),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The password contains at least one character of this class.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Requires(pub CharClass);
+
+impl PasswordPolicy for Requires {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        if pass.password.chars().any(|c| self.0.contains(c)) {
+            return None;
+        }
+        Some(Violation::of(
+            self,
+            format!("contains no {} character", self.0),
+        ))
+    }
+}
+
+impl fmt::Display for Requires {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "require:{}", self.0)
+    }
+}
+
+/// Every one of the policies holds. A violation is that of the first policy that fails.
+#[derive(Debug)]
+pub struct And(pub Vec<Box<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for And {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        self.0.iter().find_map(|p| p.violation(pass))
+    }
+}
+
+impl fmt::Display for And {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_joined(f, &self.0, " & ")
+    }
+}
+
+/// At least one of the policies holds.
+#[derive(Debug)]
+pub struct Or(pub Vec<Box<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for Or {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        let mut reasons = Vec::new();
+        for policy in &self.0 {
+            reasons.push(policy.violation(pass)?.to_string());
+        }
+        Some(Violation::of(
+            self,
+            format!("no alternative holds ({})", reasons.join("; ")),
+        ))
+    }
+}
+
+impl fmt::Display for Or {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_joined(f, &self.0, " | ")
+    }
+}
+
+/// The policy does not hold.
+#[derive(Debug)]
+pub struct Not(pub Box<dyn PasswordPolicy>);
+
+impl PasswordPolicy for Not {
+    fn violation(&self, pass: &Password) -> Option<Violation> {
+        match self.0.violation(pass) {
+            Some(_) => None,
+            None => Some(Violation::of(self, format!("satisfies `{}`", self.0))),
+        }
+    }
+}
+
+impl fmt::Display for Not {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "!{}", self.0)
+    }
+}
+
+/// Writes `policies` separated by `op`, parenthesized so the spec parses back the same
+/// way whatever it is nested in.
+fn write_joined(
+    f: &mut fmt::Formatter<'_>,
+    policies: &[Box<dyn PasswordPolicy>],
+    op: &str,
+) -> fmt::Result {
+    f.write_str("(")?;
+    for (i, policy) in policies.iter().enumerate() {
+        if i > 0 {
+            f.write_str(op)?;
+        }
+        write!(f, "{}", policy)?;
+    }
+    f.write_str(")")
+}
+
+/// Parses a policy spec.
+///
+/// A spec combines the policies `count`, `position`, `min-length:<n>`, `max-length:<n>`,
+/// `forbid:<chars>` and `require:<lower|upper|digit|symbol>` with `!` (not), `&` (and)
+/// and `|` (or), in decreasing order of precedence, and parentheses. Within `<chars>`, a
+/// `\` makes the character after it part of the set even if it would end the policy.
+pub fn parse(spec: &str) -> Result<Box<dyn PasswordPolicy>, ParseError> {
+    let mut parser = Parser { spec, pos: 0 };
+    let policy = parser.or()?;
+    match parser.peek() {
+        None => Ok(policy),
+        Some(_) => Err(parser.error(&spec[parser.pos..], "expected `&`, `|` or the end")),
+    }
+}
+
+/// Whether `c` ends a policy name or argument in a spec, unless it is escaped.
+fn ends_atom(c: char) -> bool {
+    c.is_whitespace() || "&|!()".contains(c)
+}
+
+/// Recursive-descent parser over a spec, tracking a byte offset into it.
+struct Parser<'a> {
+    spec: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, part: &str, reason: &str) -> ParseError {
+        let offset = part.as_ptr() as usize - self.spec.as_ptr() as usize;
+        let column = self.spec[..offset].chars().count() + 1;
+        ParseError::new(2, 1, column, part, reason)
+    }
+
+    /// The next non-space character, skipping spaces.
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.spec[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+        self.spec[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<Box<dyn PasswordPolicy>, ParseError> {
+        let mut policies = vec![self.and()?];
+        while self.eat('|') {
+            policies.push(self.and()?);
+        }
+        Ok(match policies.len() {
+            1 => policies.pop().unwrap(),
+            _ => Box::new(Or(policies)),
+        })
+    }
+
+    fn and(&mut self) -> Result<Box<dyn PasswordPolicy>, ParseError> {
+        let mut policies = vec![self.not()?];
+        while self.eat('&') {
+            policies.push(self.not()?);
+        }
+        Ok(match policies.len() {
+            1 => policies.pop().unwrap(),
+            _ => Box::new(And(policies)),
+        })
+    }
+
+    fn not(&mut self) -> Result<Box<dyn PasswordPolicy>, ParseError> {
+        if self.eat('!') {
+            return Ok(Box::new(Not(self.not()?)));
+        }
+        if self.eat('(') {
+            let policy = self.or()?;
+            if !self.eat(')') {
+                return Err(self.error(&self.spec[self.pos..], "expected `)`"));
+            }
+            return Ok(policy);
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Box<dyn PasswordPolicy>, ParseError> {
+        self.peek();
+        let rest = &self.spec[self.pos..];
+        let mut escaped = false;
+        let end = rest
+            .find(|c: char| match c {
+                _ if escaped => {
+                    escaped = false;
+                    false
+                }
+                '\\' => {
+                    escaped = true;
+                    false
+                }
+                _ => ends_atom(c),
+            })
+            .unwrap_or(rest.len());
+        let atom = &rest[..end];
+        self.pos += end;
+
+        let (name, arg) = match atom.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (atom, None),
+        };
+        let length = |arg: &str| {
+            arg.parse()
+                .map_err(|_| self.error(arg, "expected a length"))
+        };
+        Ok(match (name, arg) {
+            ("count", None) => Box::new(CountPolicy),
+            ("position", None) => Box::new(PositionPolicy),
+            ("min-length", Some(arg)) => Box::new(MinLength(length(arg)?)),
+            ("max-length", Some(arg)) => Box::new(MaxLength(length(arg)?)),
+            ("forbid", Some(arg)) if !arg.is_empty() => Box::new(Forbidden(self.unescape(arg)?)),
+            ("require", Some(arg)) => match arg.parse() {
+                Ok(class) => Box::new(Requires(class)),
+                Err(()) => {
+                    return Err(self.error(arg, "expected `lower`, `upper`, `digit` or `symbol`"))
+                }
+            },
+            _ => return Err(self.error(atom, "expected a policy")),
+        })
+    }
+
+    /// `arg` with each `\` removed and the character after it kept as is.
+    fn unescape(&self, arg: &str) -> Result<String, ParseError> {
+        let mut chars = arg.chars();
+        let mut unescaped = String::with_capacity(arg.len());
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(c) => unescaped.push(c),
+                    None => {
+                        let end = &arg[arg.len() - 1..];
+                        return Err(self.error(end, "expected a character after `\\`"));
+                    }
+                },
+                c => unescaped.push(c),
+            }
+        }
+        Ok(unescaped)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn policies_test() {
+        let pass = Password::new((1, 3), 'a', "abcde");
+        assert!(CountPolicy.is_valid(&pass));
+        assert!(PositionPolicy.is_valid(&pass));
+        assert!(MinLength(5).is_valid(&pass));
+        assert!(!MinLength(6).is_valid(&pass));
+        assert!(MaxLength(5).is_valid(&pass));
+        assert!(!MaxLength(4).is_valid(&pass));
+        assert!(Forbidden("xyz".into()).is_valid(&pass));
+        assert!(!Forbidden("xyze".into()).is_valid(&pass));
+        assert!(Requires(CharClass::Lower).is_valid(&pass));
+        assert!(!Requires(CharClass::Digit).is_valid(&pass));
+        assert!(Requires(CharClass::Symbol).is_valid(&Password::new((1, 3), 'a', "a#")));
+        assert!(Requires(CharClass::Upper).is_valid(&Password::new((1, 3), 'a', "aÉ")));
+    }
+
+    #[test]
+    fn position_policy_test() {
+        // Out-of-range positions don't match rather than panicking.
+        assert!(PositionPolicy.is_valid(&Password::new((1, 9), 'a', "abc")));
+        assert!(PositionPolicy.is_valid(&Password::new((0, 3), 'c', "abc")));
+        assert!(!PositionPolicy.is_valid(&Password::new((0, 4), 'c', "abc")));
+        assert!(!PositionPolicy.is_valid(&Password::new((1, 1), 'a', "")));
+        assert_eq!(
+            PositionPolicy
+                .violation(&Password::new((0, 4), 'c', "abc"))
+                .unwrap()
+                .reason,
+            "neither position 0 nor 4 matches (position 0 is outside 1-3)"
+        );
+        assert_eq!(
+            PositionPolicy
+                .violation(&Password::new((1, 1), 'a', ""))
+                .unwrap()
+                .reason,
+            "neither position 1 nor 1 matches (the password is empty)"
+        );
+
+        // Positions count characters, not bytes.
+        assert!(!PositionPolicy.is_valid(&Password::new((2, 3), 'é', "aéé")));
+        assert!(PositionPolicy.is_valid(&Password::new((2, 4), 'é', "aééx")));
+        assert!(PositionPolicy.is_valid(&Password::new((1, 2), '日', "日本")));
+        assert!(PositionPolicy.is_valid(&Password::new((3, 1), '🦀', "ab🦀")));
+        assert!(CountPolicy.is_valid(&Password::new((2, 2), 'é', "éaé")));
+    }
+
+    #[test]
+    fn combinators_test() {
+        let pass = Password::new((1, 3), 'a', "abcde");
+        let digit = || Box::new(Requires(CharClass::Digit));
+        assert!(!And(vec![Box::new(CountPolicy), digit()]).is_valid(&pass));
+        assert!(Or(vec![Box::new(CountPolicy), digit()]).is_valid(&pass));
+        assert!(Not(digit()).is_valid(&pass));
+        assert!(And(vec![]).is_valid(&pass));
+        assert!(!Or(vec![]).is_valid(&pass));
+
+        // The empty forms have no spec to display as.
+        for empty in [
+            Box::new(And(vec![])) as Box<dyn PasswordPolicy>,
+            Box::new(Or(vec![])),
+            Box::new(Forbidden(String::new())),
+        ] {
+            assert!(parse(&empty.to_string()).is_err());
+        }
+    }
+
+    #[test]
+    fn violation_test() {
+        let pass = Password::new((1, 2), 'p', "pppppppp");
+        let reason = |policy: &dyn PasswordPolicy| policy.violation(&pass).map(|v| v.reason);
+        assert_eq!(
+            reason(&CountPolicy).unwrap(),
+            "letter 'p' appears 8 times, allowed 1-2"
+        );
+        assert_eq!(
+            reason(&PositionPolicy).unwrap(),
+            "positions 1 and 2 both match"
+        );
+        assert_eq!(reason(&MinLength(9)).unwrap(), "8 characters, fewer than 9");
+        assert_eq!(reason(&MaxLength(7)).unwrap(), "8 characters, more than 7");
+        assert_eq!(reason(&MaxLength(8)), None);
+        assert_eq!(
+            reason(&Forbidden("xp".into())).unwrap(),
+            "contains forbidden character 'p'"
+        );
+        assert_eq!(
+            reason(&Requires(CharClass::Digit)).unwrap(),
+            "contains no digit character"
+        );
+
+        let pass = Password::new((1, 3), 'b', "abcde");
+        assert_eq!(
+            PositionPolicy.violation(&pass).unwrap().reason,
+            "neither position 1 nor 3 matches"
+        );
+        assert_eq!(
+            CountPolicy.violation(&Password::new((2, 9), 'c', "abcde")),
+            Some(Violation {
+                policy: "count".into(),
+                reason: "letter 'c' appears 1 time, allowed 2-9".into()
+            })
+        );
+
+        // Combinators blame the policy that actually failed.
+        let policy = parse("max-length:9 & (count | require:upper)").unwrap();
+        assert_eq!(
+            policy
+                .violation(&Password::new((2, 9), 'c', "abcde"))
+                .unwrap()
+                .to_string(),
+            "(count | require:upper): no alternative holds \
+             (count: letter 'c' appears 1 time, allowed 2-9; \
+             require:upper: contains no upper character)"
+        );
+        assert!(policy.is_valid(&pass));
+        let policy = parse("min-length:2 & !require:lower").unwrap();
+        assert_eq!(
+            policy.violation(&pass).unwrap().to_string(),
+            "!require:lower: satisfies `require:lower`"
+        );
+    }
+
+    #[test]
+    fn parse_test() {
+        let policy = parse("count & !(min-length:12 | require:digit)").unwrap();
+        assert_eq!(
+            policy.to_string(),
+            "(count & !(min-length:12 | require:digit))"
+        );
+        assert_eq!(
+            parse(&policy.to_string()).unwrap().to_string(),
+            policy.to_string()
+        );
+        assert!(policy.is_valid(&Password::new((1, 3), 'a', "abcde")));
+        assert!(!policy.is_valid(&Password::new((1, 3), 'a', "abcde1")));
+
+        // `&` binds tighter than `|`.
+        let policy = parse("position | forbid:#$ & max-length:3").unwrap();
+        assert_eq!(
+            policy.to_string(),
+            "(position | (forbid:#$ & max-length:3))"
+        );
+
+        let err = parse("count & min-length:x").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (20, "x"));
+        let err = parse("count & (position").unwrap_err();
+        assert_eq!(err.reason, "expected `)`");
+        let err = parse("count position").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (7, "position"));
+        assert!(parse("require:emoji").is_err());
+        assert!(parse("forbid:").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn forbidden_escapes_test() {
+        let policy = Forbidden("a b&(\\".into());
+        assert_eq!(policy.to_string(), "forbid:a\\ b\\&\\(\\\\");
+        let parsed = parse(&format!("!{} | count", policy)).unwrap();
+        assert_eq!(parsed.to_string(), format!("(!{} | count)", policy));
+        let parsed = parse(&policy.to_string()).unwrap();
+        assert!(!parsed.is_valid(&Password::new((2, 3), 'x', "x&y")));
+        assert!(parsed.is_valid(&Password::new((2, 3), 'x', "x|y")));
+
+        let err = parse("forbid:ab\\").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (10, "\\"));
+    }
+
+    proptest! {
+        #[test]
+        fn forbidden_round_trips(
+            chars in prop_oneof![any::<String>(), "[ \t\n&|!():a\\\\]*"]
+                .prop_filter("forbid needs a character", |s| !s.is_empty()),
+        ) {
+            let policy = Forbidden(chars);
+            let spec = format!("({} & count)", policy);
+            prop_assert_eq!(parse(&spec).unwrap().to_string(), spec);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! The extended line format, `<kind> <min>-<max> <letter>: <password>`, which names the
+//! policy each record is checked against so a file can mix policies.
+
+use super::policy::{PolicyKind, Violation};
+use super::{parse_fields, single_line, Password};
+use crate::input::{lines, Line};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+/// A password record along with the kind of policy it must satisfy.
+///
+/// Displays as `<kind> <min>-<max> <letter>: <password>`, which parses back to an equal
+/// record under the same conditions as [`Password`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Record {
+    pub kind: PolicyKind,
+    pub password: Password,
+}
+
+impl Record {
+    pub fn new(kind: PolicyKind, password: Password) -> Self {
+        Record { kind, password }
+    }
+
+    /// Why the password breaks its own policy, or `None` if it satisfies it.
+    pub fn violation(&self) -> Option<Violation> {
+        self.kind.policy().violation(&self.password)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.violation().is_none()
+    }
+}
+
+/// Every record of `input`, one per non-blank line.
+pub fn parse_records(input: &str) -> Result<Vec<Record>, ParseError> {
+    lines(2, input).map(|line| parse_record(&line)).collect()
+}
+
+fn parse_record(line: &Line) -> Result<Record, ParseError> {
+    let kind = line.text.split_whitespace().next().unwrap_or(line.text);
+    let rest = line.text[kind.len()..].trim_start();
+    let kind = kind
+        .parse()
+        .map_err(|_| line.error(kind, "expected a policy kind (`count` or `position`)"))?;
+    Ok(Record::new(kind, parse_fields(line, rest)?))
+}
+
+/// How many of `records` satisfy their own policy.
+pub fn count_valid(records: &[Record]) -> usize {
+    records.iter().filter(|r| r.is_valid()).count()
+}
+
+impl FromStr for Record {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        parse_record(&single_line(s)?)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.password)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::test::password_strategy;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parse_records_test() {
+        let input = "count 1-3 a: abcde\nposition 1-3 b: cdefg\n\nposition\t2-9 c: ccccccccc";
+        let records = parse_records(input).unwrap();
+        assert_eq!(
+            records[0],
+            Record::new(PolicyKind::Count, Password::new((1, 3), 'a', "abcde"))
+        );
+        assert_eq!(records[2].kind, PolicyKind::Position);
+        assert_eq!(count_valid(&records), 1);
+        assert_eq!(records[1].to_string(), "position 1-3 b: cdefg");
+
+        let err = parse_records("count 1-3 a: abcde\n1-3 b: cdefg").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "1-3"));
+        let err = parse_records("position 1-x b: cdefg").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 12, "x"));
+        let err = parse_records("count").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 6, ""));
+    }
+
+    #[test]
+    fn violation_test() {
+        let record: Record = "count 1-2 p: pppppppp".parse().unwrap();
+        assert_eq!(
+            record.violation().unwrap().to_string(),
+            "count: letter 'p' appears 8 times, allowed 1-2"
+        );
+        assert!("position 1-2 p: pa".parse::<Record>().unwrap().is_valid());
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trips(
+            position in any::<bool>(),
+            pass in password_strategy(),
+        ) {
+            let kind = if position { PolicyKind::Position } else { PolicyKind::Count };
+            let record = Record::new(kind, pass);
+            prop_assert_eq!(record.to_string().parse::<Record>().unwrap(), record);
+        }
+    }
+}
@@ -1,45 +1,128 @@
+use crate::grid::Grid;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::fmt;
+use terrain::{Legend, Terrain};
+
+pub mod route;
+pub mod slopes;
+pub mod terrain;
 
 #[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Map {
-    let width = input.lines().next().unwrap().len();
-    let height = input.lines().count();
-    let mut map = Vec::new();
-    input.lines().for_each(|l| {
-        l.chars().for_each(|ch| map.push(ch == '#'));
-    });
-    Map { width, height, map }
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    Map::parse(input, &Legend::default())
 }
 
 #[aoc(day3, part1)]
-pub fn solve_part1(map: &Map) -> usize {
+pub fn solve_part1(map: &Map) -> Option<usize> {
     tree_count(map, 3, 1)
 }
 
 #[aoc(day3, part2)]
-pub fn solve_part2(map: &Map) -> usize {
-    let slopes = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+pub fn solve_part2(map: &Map) -> Option<usize> {
+    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
     slopes.iter().map(|&(x, y)| tree_count(map, x, y)).product()
 }
 
-fn tree_count(map: &Map, slope_x: usize, slope_y: usize) -> usize {
-    (0..)
-        .step_by(slope_x)
-        .zip((0..map.height).step_by(slope_y))
-        .map(|(x, y)| map.is_tree(x, y))
-        .filter(|&tree| tree)
-        .count()
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+}
+
+/// How many trees the route going `slope_x` right and `slope_y` down hits, or `None` if
+/// the slope doesn't move.
+pub fn tree_count(map: &Map, slope_x: usize, slope_y: usize) -> Option<usize> {
+    route::trace(map, slope_x, slope_y).map(|route| route.filter(|v| v.tree).count())
 }
 
+/// A map whose squares are terrain from a legend, trees and open ground by default.
+#[derive(Debug)]
 pub struct Map {
-    width: usize,
-    height: usize,
-    map: Vec<bool>,
+    /// Each square's position in the legend.
+    grid: Grid<usize>,
+    legend: Legend,
 }
 
 impl Map {
-    fn is_tree(&self, x: usize, y: usize) -> bool {
-        self.map[y * self.width + (x % self.width)]
+    /// Builds a map with the default legend from row-major cells, where `true` marks a
+    /// tree, or returns `None` if the map is empty or isn't `width * height` cells.
+    pub fn new(width: usize, height: usize, map: Vec<bool>) -> Option<Self> {
+        if width == 0 || height == 0 || width.checked_mul(height)? != map.len() {
+            return None;
+        }
+        let legend = Legend::default();
+        let (tree, open) = (legend.position('#')?, legend.position('.')?);
+        let cells = map.into_iter().map(|t| if t { tree } else { open });
+        Some(Map {
+            grid: Grid::new(width, height, cells.collect()),
+            legend,
+        })
+    }
+
+    /// Parses a map drawn with the symbols of `legend`.
+    pub fn parse(input: &str, legend: &Legend) -> Result<Self, ParseError> {
+        let grid = Grid::parse(3, input, &legend.expected(), |c| legend.position(c))?;
+        if grid.is_empty() {
+            return Err(ParseError::empty(3));
+        }
+        Ok(Map {
+            grid,
+            legend: legend.clone(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    pub fn legend(&self) -> &Legend {
+        &self.legend
+    }
+
+    /// The terrain at `(x, y)`, repeating the pattern in every direction.
+    pub fn terrain(&self, x: isize, y: isize) -> &Terrain {
+        &self.legend.terrains()[*self.grid.get_wrapping(x, y)]
+    }
+
+    /// Whether `(x, y)` is a tree, repeating the pattern to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is below the bottom row.
+    pub fn is_tree(&self, x: usize, y: usize) -> bool {
+        assert!(y < self.height(), "row {} is below the map", y);
+        self.terrain(x as isize, y as isize).is_tree()
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols = self.grid.map(|_, &i| self.legend.terrains()[i].symbol);
+        write!(f, "{}", symbols)
     }
 }
 
@@ -49,13 +132,48 @@ mod test {
 
     #[test]
     fn tree_count_test() {
-        let map = Map {
-            width: 2,
-            height: 2,
-            map: vec![true, true, false, true],
-        };
-
-        assert_eq!(tree_count(&map, 1, 1), 2);
-        assert_eq!(tree_count(&map, 2, 1), 1);
+        let map = Map::new(2, 2, vec![true, true, false, true]).unwrap();
+
+        assert_eq!(tree_count(&map, 1, 1), Some(2));
+        assert_eq!(tree_count(&map, 2, 1), Some(1));
+        assert_eq!(tree_count(&map, 0, 1), Some(1));
+        assert_eq!(tree_count(&map, 0, 0), None);
+    }
+
+    #[test]
+    fn new_test() {
+        assert!(Map::new(0, 0, vec![]).is_none());
+        assert!(Map::new(2, 0, vec![]).is_none());
+        assert!(Map::new(2, 2, vec![true]).is_none());
+        assert!(Map::new(usize::MAX, 2, vec![true]).is_none());
+    }
+
+    #[test]
+    fn input_generator_test() {
+        let map = input_generator("..#\n#..").unwrap();
+        assert_eq!((map.width(), map.height()), (3, 2));
+        assert!(map.is_tree(2, 0) && !map.is_tree(4, 1));
+        assert_eq!(map.to_string(), "..#\n#..\n");
+
+        let err = input_generator("..#\n#.").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = input_generator("..#\n#o.").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "o."));
+
+        assert_eq!(input_generator("").unwrap_err(), ParseError::empty(3));
+    }
+
+    #[test]
+    fn parse_test() {
+        let legend = Legend::parse("# tree 1\n. open 0\n~ water 5").unwrap();
+        let map = Map::parse("~.#\n#~.", &legend).unwrap();
+        assert_eq!(map.terrain(-1, 1).name, "open");
+        assert!(!map.is_tree(0, 0) && map.is_tree(2, 0));
+        assert_eq!(tree_count(&map, 1, 1), Some(0));
+        assert_eq!(tree_count(&map, 3, 1), Some(1));
+
+        let err = Map::parse("~.#\n#o.", &legend).unwrap_err();
+        assert_eq!(err.reason, "expected `#`, `.` or `~`");
     }
 }
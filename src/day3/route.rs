@@ -0,0 +1,252 @@
+//! Routes down the slope square by square, and a renderer that draws a route over the
+//! map for debugging.
+
+use super::Map;
+use num::rational::Ratio;
+use std::collections::HashMap;
+
+/// A square a route visits. Coordinates count squares across the repeated pattern, so
+/// they can fall outside the map, or below zero for routes that go left or up.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Visit {
+    pub x: isize,
+    pub y: isize,
+    /// Whether the square is a tree.
+    pub tree: bool,
+    /// The symbol of the terrain landed on.
+    pub terrain: char,
+}
+
+/// How far a route moves each step, negative values going left or up.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Slope {
+    right: isize,
+    down: isize,
+}
+
+impl Slope {
+    /// A slope that moves `right` and `down` each step, or `None` if it doesn't move.
+    pub fn new(right: isize, down: isize) -> Option<Self> {
+        if (right, down) == (0, 0) {
+            return None;
+        }
+        Some(Slope { right, down })
+    }
+
+    pub fn right(self) -> isize {
+        self.right
+    }
+
+    pub fn down(self) -> isize {
+        self.down
+    }
+}
+
+impl From<Ratio<isize>> for Slope {
+    /// The slope that moves `ratio` squares right for every square down, stepping to
+    /// the next square it passes exactly through: `1/3` goes 1 right and 3 down.
+    fn from(ratio: Ratio<isize>) -> Self {
+        Slope {
+            right: *ratio.numer(),
+            down: *ratio.denom(),
+        }
+    }
+}
+
+/// Which edges of the map a route passes through to the other side. A route ends just
+/// before it would leave through an edge that doesn't wrap, or come back to the square
+/// it started on, so every route is finite.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Topology {
+    /// The pattern repeats left and right; the route ends at the top or bottom, or
+    /// after one trip around if it only moves sideways.
+    HorizontalWrap,
+    /// The pattern repeats in every direction; the route ends when it gets back to the
+    /// starting square.
+    Torus,
+    /// Nothing repeats; the route ends at any edge.
+    Bounded,
+}
+
+impl Topology {
+    fn wraps(self) -> (bool, bool) {
+        match self {
+            Topology::HorizontalWrap => (true, false),
+            Topology::Torus => (true, true),
+            Topology::Bounded => (false, false),
+        }
+    }
+}
+
+/// The squares visited going `slope` from the top left over `map`, the starting square
+/// included, until the route ends as `topology` says.
+pub fn walk(map: &Map, slope: Slope, topology: Topology) -> Walk<'_> {
+    Walk {
+        map,
+        slope,
+        topology,
+        next: Some((0, 0)),
+    }
+}
+
+/// Iterator returned by [`walk`].
+#[derive(Debug, Clone)]
+pub struct Walk<'a> {
+    map: &'a Map,
+    slope: Slope,
+    topology: Topology,
+    next: Option<(isize, isize)>,
+}
+
+impl Walk<'_> {
+    fn step(&self, (x, y): (isize, isize)) -> Option<(isize, isize)> {
+        let x = x.checked_add(self.slope.right)?;
+        let y = y.checked_add(self.slope.down)?;
+        let (width, height) = (self.map.width() as isize, self.map.height() as isize);
+        let (wrap_x, wrap_y) = self.topology.wraps();
+        let inside = |pos: isize, size: isize, wraps: bool| wraps || (0..size).contains(&pos);
+        if !inside(x, width, wrap_x) || !inside(y, height, wrap_y) {
+            return None;
+        }
+        if (x.rem_euclid(width), y.rem_euclid(height)) == (0, 0) {
+            return None;
+        }
+        Some((x, y))
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = Visit;
+
+    fn next(&mut self) -> Option<Visit> {
+        let (x, y) = self.next?;
+        self.next = self.step((x, y));
+        let terrain = self.map.terrain(x, y);
+        Some(Visit {
+            x,
+            y,
+            tree: terrain.is_tree(),
+            terrain: terrain.symbol,
+        })
+    }
+}
+
+/// Every square visited going `slope_x` right and `slope_y` down from the top left until
+/// the route leaves the bottom of the map, the starting square included, or `None` if
+/// the slope doesn't move.
+pub fn trace(map: &Map, slope_x: usize, slope_y: usize) -> Option<Walk<'_>> {
+    let slope = Slope::new(slope_x as isize, slope_y as isize)?;
+    Some(walk(map, slope, Topology::HorizontalWrap))
+}
+
+/// The map with `route` drawn over it, `O` where it hits a tree and `X` on any other
+/// square it visits. The pattern repeats in whichever directions the route goes, and
+/// every row ends with a newline.
+pub fn render(map: &Map, route: impl IntoIterator<Item = Visit>) -> String {
+    let visits: HashMap<(isize, isize), bool> =
+        route.into_iter().map(|v| ((v.x, v.y), v.tree)).collect();
+    let (width, height) = (map.width() as isize, map.height() as isize);
+    // The span of copies of the map the route reaches, always including the original.
+    let (mut left, mut right, mut top, mut bottom) = (0, 0, 0, 0);
+    for &(x, y) in visits.keys() {
+        left = x.div_euclid(width).min(left);
+        right = x.div_euclid(width).max(right);
+        top = y.div_euclid(height).min(top);
+        bottom = y.div_euclid(height).max(bottom);
+    }
+
+    let mut out = String::new();
+    for y in top * height..(bottom + 1) * height {
+        for x in left * width..(right + 1) * width {
+            out.push(match visits.get(&(x, y)) {
+                Some(true) => 'O',
+                Some(false) => 'X',
+                None => map.terrain(x, y).symbol,
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day3::input_generator;
+    use crate::day3::terrain::Legend;
+
+    fn squares(route: Walk) -> Vec<(isize, isize, bool)> {
+        route.map(|v| (v.x, v.y, v.tree)).collect()
+    }
+
+    #[test]
+    fn trace_test() {
+        let map = input_generator("..#\n#..\n.#.").unwrap();
+        let route = squares(trace(&map, 2, 1).unwrap());
+        assert_eq!(route, vec![(0, 0, false), (2, 1, false), (4, 2, true)]);
+        let route = squares(trace(&map, 0, 2).unwrap());
+        assert_eq!(route, vec![(0, 0, false), (0, 2, false)]);
+        assert!(trace(&map, 0, 0).is_none());
+    }
+
+    #[test]
+    fn slope_test() {
+        assert_eq!(Slope::new(0, 0), None);
+        let slope = Slope::from(Ratio::new(-2, 6));
+        assert_eq!((slope.right(), slope.down()), (-1, 3));
+        assert_eq!(
+            Slope::from(Ratio::from_integer(3)),
+            Slope::new(3, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn walk_test() {
+        let map = input_generator("..#\n#..\n.#.").unwrap();
+        let route =
+            |right, down, topology| squares(walk(&map, Slope::new(right, down).unwrap(), topology));
+
+        use Topology::*;
+        assert_eq!(
+            route(-1, 1, HorizontalWrap),
+            vec![(0, 0, false), (-1, 1, false), (-2, 2, true)]
+        );
+        assert_eq!(route(1, -1, HorizontalWrap), vec![(0, 0, false)]);
+        assert_eq!(
+            route(2, 0, HorizontalWrap),
+            vec![(0, 0, false), (2, 0, true), (4, 0, false)]
+        );
+        assert_eq!(
+            route(1, 2, Torus),
+            vec![(0, 0, false), (1, 2, true), (2, 4, false)]
+        );
+        assert_eq!(route(0, -1, Torus).len(), 3);
+        assert_eq!(route(2, 1, Bounded), vec![(0, 0, false), (2, 1, false)]);
+        assert_eq!(route(-1, 1, Bounded), vec![(0, 0, false)]);
+
+        let third = walk(&map, Slope::from(Ratio::new(1, 2)), HorizontalWrap);
+        assert_eq!(squares(third), vec![(0, 0, false), (1, 2, true)]);
+    }
+
+    #[test]
+    fn render_test() {
+        let map = input_generator("..#\n#..\n.#.").unwrap();
+        assert_eq!(
+            render(&map, trace(&map, 2, 1).unwrap()),
+            "X.#..#\n#.X#..\n.#..O.\n"
+        );
+        assert_eq!(render(&map, trace(&map, 1, 2).unwrap()), "X.#\n#..\n.O.\n");
+        assert_eq!(render(&map, None), "..#\n#..\n.#.\n");
+
+        let left = walk(&map, Slope::new(-1, 1).unwrap(), Topology::HorizontalWrap);
+        assert_eq!(render(&map, left), "..#X.#\n#.X#..\n.O..#.\n");
+        let torus = walk(&map, Slope::new(1, 2).unwrap(), Topology::Torus);
+        assert_eq!(render(&map, torus), "X.#\n#..\n.O.\n..#\n#.X\n.#.\n");
+
+        // Costly terrain other than trees is drawn like open ground.
+        let legend = Legend::parse("# tree 1\n. open 0\n~ water 5").unwrap();
+        let map = Map::parse("~.#\n#~.", &legend).unwrap();
+        assert_eq!(render(&map, trace(&map, 1, 1).unwrap()), "X.#\n#X.\n");
+        assert_eq!(render(&map, trace(&map, 3, 1).unwrap()), "X.#~.#\n#~.O~.\n");
+    }
+}
@@ -0,0 +1,110 @@
+//! Searches over every slope within bounds for the route that hits the fewest or the
+//! most trees.
+
+use super::{tree_count, Map};
+use std::cmp::Reverse;
+use std::ops::RangeInclusive;
+
+/// A slope, `(right, down)`, and how many trees its route hits.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct RankedSlope {
+    pub slope: (usize, usize),
+    pub trees: usize,
+}
+
+/// Which end of the ranking comes first.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Order {
+    FewestTrees,
+    MostTrees,
+}
+
+/// Every slope with a rightward step in `right` and a downward step in `down`, ranked by
+/// tree count in `order`. Slopes that hit as many trees keep their relative order by
+/// `(right, down)`, smallest first. A downward step of 0 never reaches the bottom, so
+/// it is skipped.
+pub fn rank_slopes(
+    map: &Map,
+    right: RangeInclusive<usize>,
+    down: RangeInclusive<usize>,
+    order: Order,
+) -> Vec<RankedSlope> {
+    let mut ranked: Vec<RankedSlope> = right
+        .flat_map(|x| down.clone().filter(|&y| y > 0).map(move |y| (x, y)))
+        .filter_map(|(x, y)| {
+            Some(RankedSlope {
+                slope: (x, y),
+                trees: tree_count(map, x, y)?,
+            })
+        })
+        .collect();
+    match order {
+        Order::FewestTrees => ranked.sort_by_key(|r| (r.trees, r.slope)),
+        Order::MostTrees => ranked.sort_by_key(|r| (Reverse(r.trees), r.slope)),
+    }
+    ranked
+}
+
+/// The slope within bounds that hits the fewest trees, or `None` if there are no slopes.
+pub fn fewest_trees(
+    map: &Map,
+    right: RangeInclusive<usize>,
+    down: RangeInclusive<usize>,
+) -> Option<RankedSlope> {
+    rank_slopes(map, right, down, Order::FewestTrees)
+        .into_iter()
+        .next()
+}
+
+/// The slope within bounds that hits the most trees, or `None` if there are no slopes.
+pub fn most_trees(
+    map: &Map,
+    right: RangeInclusive<usize>,
+    down: RangeInclusive<usize>,
+) -> Option<RankedSlope> {
+    rank_slopes(map, right, down, Order::MostTrees)
+        .into_iter()
+        .next()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day3::input_generator;
+
+    const EXAMPLE: &str = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n.#...##..#.\n\
+                           ..#.##.....\n.#.#.#....#\n.#........#\n#.##...#...\n#...##....#\n\
+                           .#..#...#.#";
+
+    #[test]
+    fn rank_slopes_test() {
+        let map = input_generator(EXAMPLE).unwrap();
+        let ranked = rank_slopes(&map, 1..=7, 1..=2, Order::MostTrees);
+        assert_eq!(ranked.len(), 14);
+        assert_eq!(
+            ranked[0],
+            RankedSlope {
+                slope: (3, 1),
+                trees: 7
+            }
+        );
+        assert!(ranked.windows(2).all(|w| w[0].trees >= w[1].trees));
+
+        let ranked = rank_slopes(&map, 1..=7, 0..=2, Order::FewestTrees);
+        assert_eq!(ranked.len(), 14);
+        assert!(ranked
+            .windows(2)
+            .all(|w| (w[0].trees, w[0].slope) < (w[1].trees, w[1].slope)));
+        assert_eq!(ranked.iter().find(|r| r.slope == (1, 2)).unwrap().trees, 2);
+    }
+
+    #[test]
+    fn extremes_test() {
+        let map = input_generator(EXAMPLE).unwrap();
+        // (0, 2), (2, 2) and (4, 2) each hit one tree; the smallest slope wins.
+        assert_eq!(fewest_trees(&map, 0..=4, 2..=2).unwrap().slope, (0, 2));
+        assert_eq!(fewest_trees(&map, 0..=7, 1..=2).unwrap().slope, (5, 2));
+        assert_eq!(most_trees(&map, 0..=7, 1..=1).unwrap().slope, (3, 1));
+        assert_eq!(fewest_trees(&map, 0..=3, 0..=0), None);
+    }
+}
@@ -0,0 +1,240 @@
+//! Legends of the terrain a map may contain, what crossing each kind costs, and the
+//! per-terrain cost of a route.
+//!
+//! A legend is written one terrain per line as `<symbol> <name> <cost>`, and must name
+//! one terrain exactly `tree` for tree counts to find; the default legend, for the
+//! puzzle's maps, is
+//!
+//! ```text
+//! # tree 1
+//! . open 0
+//! ```
+
+use super::route::Visit;
+use super::Map;
+use crate::input::lines;
+use crate::ParseError;
+
+/// A kind of square a map can contain.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Terrain {
+    /// The character that marks it on the map.
+    pub symbol: char,
+    pub name: String,
+    /// What landing on one of its squares costs.
+    pub cost: u64,
+}
+
+impl Terrain {
+    pub fn new(symbol: char, name: impl Into<String>, cost: u64) -> Self {
+        Terrain {
+            symbol,
+            name: name.into(),
+            cost,
+        }
+    }
+
+    /// Whether this is the terrain named `tree`, the only one tree counts include
+    /// whatever the other terrains cost.
+    pub fn is_tree(&self) -> bool {
+        self.name == "tree"
+    }
+}
+
+/// The terrains a map may contain, in the order costs are reported.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Legend {
+    terrains: Vec<Terrain>,
+}
+
+impl Legend {
+    /// # Panics
+    ///
+    /// Panics if two terrains share a symbol or none is the tree terrain.
+    pub fn new(terrains: Vec<Terrain>) -> Self {
+        for (i, terrain) in terrains.iter().enumerate() {
+            assert!(
+                terrains[..i].iter().all(|t| t.symbol != terrain.symbol),
+                "symbol {:?} is used twice",
+                terrain.symbol
+            );
+        }
+        assert!(
+            terrains.iter().any(Terrain::is_tree),
+            "no terrain is named `tree`"
+        );
+        Legend { terrains }
+    }
+
+    /// Parses a legend written one terrain per line as `<symbol> <name> <cost>`, one of
+    /// which must be named `tree`.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut terrains: Vec<Terrain> = Vec::new();
+        for line in lines(3, input) {
+            let parts: Vec<&str> = line.text.split_whitespace().collect();
+            let (symbol, name, cost) = match parts[..] {
+                [symbol, name, cost] => (symbol, name, cost),
+                _ => return Err(line.error(line.text, "expected `<symbol> <name> <cost>`")),
+            };
+            let mut chars = symbol.chars();
+            let symbol_char = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(line.error(symbol, "expected a single character")),
+            };
+            if terrains.iter().any(|t| t.symbol == symbol_char) {
+                return Err(line.error(symbol, "expected a symbol not already in the legend"));
+            }
+            let cost = line.parse(cost, "a cost")?;
+            terrains.push(Terrain::new(symbol_char, name, cost));
+        }
+        if terrains.is_empty() {
+            return Err(ParseError::empty(3));
+        }
+        if !terrains.iter().any(Terrain::is_tree) {
+            return Err(ParseError::new(
+                3,
+                1,
+                1,
+                "",
+                "expected a terrain named `tree`",
+            ));
+        }
+        Ok(Legend { terrains })
+    }
+
+    pub fn terrains(&self) -> &[Terrain] {
+        &self.terrains
+    }
+
+    /// Where the terrain marked `symbol` is in the legend.
+    pub fn position(&self, symbol: char) -> Option<usize> {
+        self.terrains.iter().position(|t| t.symbol == symbol)
+    }
+
+    /// The symbols as a parse error lists them, such as "`#` or `.`".
+    pub(super) fn expected(&self) -> String {
+        let symbols: Vec<String> = self
+            .terrains
+            .iter()
+            .map(|t| format!("`{}`", t.symbol))
+            .collect();
+        match symbols.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => String::from("nothing"),
+        }
+    }
+}
+
+impl Default for Legend {
+    /// Trees, which cost 1, and open ground, which costs nothing.
+    fn default() -> Self {
+        Legend::new(vec![
+            Terrain::new('#', "tree", 1),
+            Terrain::new('.', "open", 0),
+        ])
+    }
+}
+
+/// How often a route lands on one terrain and what that costs in total.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TerrainCost {
+    pub symbol: char,
+    pub name: String,
+    pub visits: usize,
+    pub cost: u64,
+}
+
+/// The cost of a route broken down by terrain, with an entry for every terrain in the
+/// legend, in legend order.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CostBreakdown {
+    pub terrains: Vec<TerrainCost>,
+}
+
+impl CostBreakdown {
+    pub fn total(&self) -> u64 {
+        self.terrains.iter().map(|t| t.cost).sum()
+    }
+
+    pub fn get(&self, symbol: char) -> Option<&TerrainCost> {
+        self.terrains.iter().find(|t| t.symbol == symbol)
+    }
+}
+
+/// What following `route` over `map` costs, terrain by terrain, or `None` if the route
+/// lands on a terrain that isn't in the map's legend.
+pub fn route_cost(map: &Map, route: impl IntoIterator<Item = Visit>) -> Option<CostBreakdown> {
+    let legend = map.legend();
+    let mut terrains: Vec<TerrainCost> = legend
+        .terrains()
+        .iter()
+        .map(|t| TerrainCost {
+            symbol: t.symbol,
+            name: t.name.clone(),
+            visits: 0,
+            cost: 0,
+        })
+        .collect();
+    for visit in route {
+        let i = legend.position(visit.terrain)?;
+        terrains[i].visits += 1;
+        terrains[i].cost += legend.terrains[i].cost;
+    }
+    Some(CostBreakdown { terrains })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day3::route::{trace, walk, Slope, Topology};
+
+    #[test]
+    fn legend_test() {
+        let legend = Legend::parse("# tree 1\n. open 0\n~ water 5").unwrap();
+        assert_eq!(legend.terrains()[2], Terrain::new('~', "water", 5));
+        assert_eq!(legend.position('~'), Some(2));
+        assert_eq!(legend.expected(), "`#`, `.` or `~`");
+        assert_eq!(Legend::default().expected(), "`#` or `.`");
+
+        let err = Legend::parse("# tree 1\n## forest 2").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "##"));
+        let err = Legend::parse("# tree 1\n# forest 2").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "#"));
+        let err = Legend::parse("# tree -1").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 8, "-1"));
+        assert!(Legend::parse("# tree").is_err());
+        assert_eq!(Legend::parse("\n").unwrap_err(), ParseError::empty(3));
+
+        // Tree counts only recognise the terrain named exactly `tree`.
+        for legend in &["# trees 1\n. open 0", "# Tree 1\n. open 0"] {
+            let err = Legend::parse(legend).unwrap_err();
+            assert_eq!(err.reason, "expected a terrain named `tree`");
+        }
+    }
+
+    #[test]
+    fn route_cost_test() {
+        let legend = Legend::parse(". open 0\n# tree 1\n~ water 5\n^ rock 2").unwrap();
+        let map = Map::parse("..#~\n#~..\n.#~.", &legend).unwrap();
+        let costs = route_cost(&map, trace(&map, 1, 1).unwrap()).unwrap();
+        let summary: Vec<_> = costs
+            .terrains
+            .iter()
+            .map(|t| (t.symbol, t.visits, t.cost))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![('.', 1, 0), ('#', 0, 0), ('~', 2, 10), ('^', 0, 0)]
+        );
+        assert_eq!(costs.total(), 10);
+        assert_eq!(costs.get('~').unwrap().name, "water");
+
+        let route = walk(&map, Slope::new(-1, 1).unwrap(), Topology::Bounded);
+        assert_eq!(route_cost(&map, route).unwrap().total(), 0);
+
+        // A route over another map may land on terrain this legend doesn't have.
+        let default = Map::new(1, 1, vec![false]).unwrap();
+        assert_eq!(route_cost(&default, trace(&map, 1, 1).unwrap()), None);
+    }
+}
@@ -1,41 +1,36 @@
+use crate::input::groups;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::str::FromStr;
+use schema::default_schema;
+use std::collections::HashMap;
+
+pub mod schema;
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<Passport> {
-    input
-        .split("\n\n")
-        .map(|x| x.replace("\n", " "))
-        .map(|x| parse_passport(x.as_str()))
+pub fn input_generator(input: &str) -> Result<Vec<Passport>, ParseError> {
+    groups(4, input)
+        .iter()
+        .map(|group| {
+            let mut passport = Passport::default();
+            for line in group {
+                for part in line.text.split_whitespace() {
+                    match part.split_once(':') {
+                        Some((key, val)) => passport.set(key, val),
+                        None => return Err(line.error(part, "expected `<key>:<value>`")),
+                    }
+                }
+            }
+            Ok(passport)
+        })
         .collect()
 }
 
-fn parse_passport(line: &str) -> Passport {
-    let mut passport: Passport = Default::default();
-    line.split(' ').for_each(|part| {
-        let pair: Vec<_> = part.splitn(2, ':').collect();
-        let key = pair[0];
-        let val = pair[1];
-        match key {
-            "byr" => passport.byr = Some(val.to_owned()),
-            "iyr" => passport.iyr = Some(val.to_owned()),
-            "eyr" => passport.eyr = Some(val.to_owned()),
-            "hgt" => passport.hgt = Some(val.to_owned()),
-            "hcl" => passport.hcl = Some(val.to_owned()),
-            "ecl" => passport.ecl = Some(val.to_owned()),
-            "pid" => passport.pid = Some(val.to_owned()),
-            "cid" => passport.cid = Some(val.to_owned()),
-            _ => {}
-        }
-    });
-    passport
-}
-
 #[aoc(day4, part1)]
 pub fn solve_part1(passports: &[Passport]) -> usize {
     passports
         .iter()
-        .filter(|&p| p.are_required_fields_present())
+        .filter(|&p| default_schema().has_required_fields(p))
         .count()
 }
 
@@ -43,143 +38,52 @@ pub fn solve_part1(passports: &[Passport]) -> usize {
 pub fn solve_part2(passports: &[Passport]) -> usize {
     passports
         .iter()
-        .filter(|&p| p.are_required_fields_present() && p.are_required_fields_valid())
+        .filter(|&p| default_schema().is_valid(p))
         .count()
 }
 
-#[derive(Default, Debug)]
-pub struct Passport {
-    byr: Option<String>,
-    iyr: Option<String>,
-    eyr: Option<String>,
-    hgt: Option<String>,
-    hcl: Option<String>,
-    ecl: Option<String>,
-    pid: Option<String>,
-    cid: Option<String>,
-}
+pub struct Day4;
 
-impl Passport {
-    fn are_required_fields_present(&self) -> bool {
-        self.byr.is_some()
-            && self.iyr.is_some()
-            && self.eyr.is_some()
-            && self.hgt.is_some()
-            && self.hcl.is_some()
-            && self.ecl.is_some()
-            && self.pid.is_some()
-    }
+impl Solution for Day4 {
+    const DAY: u8 = 4;
 
-    fn are_required_fields_valid(&self) -> bool {
-        self.is_byr_valid()
-            && self.is_iyr_valid()
-            && self.is_eyr_valid()
-            && self.is_hgt_valid()
-            && self.is_hcl_valid()
-            && self.is_ecl_valid()
-            && self.is_pid_valid()
-            && self.is_cid_valid()
-    }
+    type Input = Vec<Passport>;
 
-    fn is_byr_valid(&self) -> bool {
-        if let Some(year) = &self.byr {
-            return if let Ok(year) = u32::from_str(year) {
-                year >= 1920 && year <= 2002
-            } else {
-                false
-            };
-        }
-        false
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
-    fn is_iyr_valid(&self) -> bool {
-        if let Some(year) = &self.iyr {
-            return if let Ok(year) = u32::from_str(year) {
-                year >= 2010 && year <= 2020
-            } else {
-                false
-            };
-        }
-        false
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part1(input).into())
     }
 
-    fn is_eyr_valid(&self) -> bool {
-        if let Some(year) = &self.eyr {
-            return if let Ok(year) = u32::from_str(year) {
-                year >= 2020 && year <= 2030
-            } else {
-                false
-            };
-        }
-        false
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part2(input).into())
     }
+}
 
-    fn is_hgt_valid(&self) -> bool {
-        if self.hgt.is_none() {
-            return false;
-        }
-        let re = regex::Regex::new("^(?P<height>\\d+)(?P<unit>cm|in)$").unwrap();
-
-        let captures = re.captures(&self.hgt.as_ref().unwrap());
-        if captures.is_none() {
-            return false;
-        }
-        let captures = captures.unwrap();
-        if captures.len() < 2 {
-            return false;
-        }
-
-        if let (Some(height), Some(unit)) = (captures.name("height"), captures.name("unit")) {
-            let height = u32::from_str(height.as_str()).ok();
-            if height.is_none() {
-                return false;
-            }
-            let height = height.unwrap();
-            return match unit.as_str() {
-                "cm" => height >= 150 && height <= 193,
-                "in" => height >= 59 && height <= 76,
-                _ => panic!("unknown unit: {}", unit.as_str()),
-            };
-        }
-        false
-    }
+/// A passport's fields by name, checked against a [`schema::Schema`].
+#[derive(Default, Debug, Clone)]
+pub struct Passport {
+    fields: HashMap<String, String>,
+}
 
-    fn is_hcl_valid(&self) -> bool {
-        if let Some(color) = &self.hcl {
-            return regex::Regex::new("^#[0-9a-f]{6}$")
-                .unwrap()
-                .is_match(color.as_str());
-        }
-        false
+impl Passport {
+    /// Stores `val` under the field named `key`, replacing any earlier value.
+    pub fn set(&mut self, key: &str, val: &str) {
+        self.fields.insert(key.to_owned(), val.to_owned());
     }
 
-    fn is_ecl_valid(&self) -> bool {
-        if let Some(color) = &self.ecl {
-            return match color.as_str() {
-                "amb" => true,
-                "blu" => true,
-                "brn" => true,
-                "gry" => true,
-                "grn" => true,
-                "hzl" => true,
-                "oth" => true,
-                _ => false,
-            };
-        }
-        false
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str)
     }
 
-    fn is_pid_valid(&self) -> bool {
-        if let Some(pid) = &self.pid {
-            return regex::Regex::new("^[0-9]{9}$")
-                .unwrap()
-                .is_match(pid.as_str());
-        }
-        false
+    pub fn are_required_fields_present(&self) -> bool {
+        default_schema().has_required_fields(self)
     }
 
-    fn is_cid_valid(&self) -> bool {
-        true
+    pub fn are_required_fields_valid(&self) -> bool {
+        default_schema().is_valid(self)
     }
 }
 
@@ -203,14 +107,14 @@ hgt:179cm
 hcl:#cfa07d eyr:2025 pid:166559648
 iyr:2011 ecl:brn hgt:59in";
 
-        let passports = input_generator(input);
+        let passports = input_generator(input).unwrap();
         assert_eq!(solve_part1(passports.as_slice()), 2);
 
         let input = "hcl:#866857 pid:983640144 hgt:61cm
 ecl:hzl
 byr:1991
 iyr:1930 eyr:2024";
-        let passports = input_generator(input);
+        let passports = input_generator(input).unwrap();
         assert_eq!(solve_part1(passports.as_slice()), 1);
     }
 
@@ -228,7 +132,7 @@ pid:545766238 ecl:hzl
 eyr:2022
 
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
-        let passports = input_generator(input);
+        let passports = input_generator(input).unwrap();
         assert_eq!(solve_part2(passports.as_slice()), 4);
 
         let input = "eyr:1972 cid:100
@@ -244,7 +148,18 @@ ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
 hgt:59cm ecl:zzz
 eyr:2038 hcl:74454a iyr:2023
 pid:3556412378 byr:2007";
-        let passports = input_generator(input);
+        let passports = input_generator(input).unwrap();
         assert_eq!(solve_part2(passports.as_slice()), 0);
     }
+
+    #[test]
+    fn input_generator_test() {
+        let passports = input_generator("ecl:gry pid:860033327\nbyr:1937\n\niyr:2013").unwrap();
+        assert_eq!(passports.len(), 2);
+        assert_eq!(passports[0].get("byr"), Some("1937"));
+        assert_eq!(passports[1].get("iyr"), Some("2013"));
+
+        let err = input_generator("ecl:gry\n\nbyr:1937 iyr").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 10, "iyr"));
+    }
 }
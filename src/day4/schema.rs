@@ -0,0 +1,305 @@
+//! Declarative passport schemas: which fields a passport has, which are required and the
+//! rules their values must satisfy, loaded from TOML. `passport.toml` holds the
+//! puzzle's rules and documents the format.
+
+use super::Passport;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::{error, fmt, fs, io};
+
+lazy_static! {
+    static ref DEFAULT_SCHEMA: Schema =
+        Schema::from_toml(include_str!("passport.toml")).expect("the default schema is valid");
+}
+
+/// The schema with the puzzle's rules.
+pub fn default_schema() -> &'static Schema {
+    &DEFAULT_SCHEMA
+}
+
+/// The fields of a passport and the rules they follow.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+/// One field of a [`Schema`].
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub required: bool,
+    /// Rules a value must satisfy, all of them.
+    pub rules: Vec<Rule>,
+}
+
+/// A rule a field's value must satisfy.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    /// A whole number within the range.
+    Int(Limits),
+    /// Matches the regex, which must be anchored to match the whole value, as schema
+    /// patterns are when they are compiled.
+    Regex(Regex),
+    /// One of the listed values.
+    Enum(Vec<String>),
+    /// A whole number directly followed by one of the units, within that unit's range.
+    Unit(BTreeMap<String, Limits>),
+}
+
+/// An inclusive range of whole numbers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Limits {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Limits {
+    fn contains(self, number: &str) -> bool {
+        matches!(number.parse(), Ok(n) if (self.min..=self.max).contains(&n))
+    }
+}
+
+impl Rule {
+    pub fn accepts(&self, value: &str) -> bool {
+        match self {
+            Rule::Int(limits) => limits.contains(value),
+            Rule::Regex(re) => re.is_match(value),
+            Rule::Enum(values) => values.iter().any(|v| v == value),
+            Rule::Unit(units) => {
+                let split = value
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(value.len());
+                let (number, unit) = value.split_at(split);
+                match units.get(unit) {
+                    Some(limits) => !number.is_empty() && limits.contains(number),
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+impl Field {
+    /// Whether `value` satisfies every rule of this field.
+    pub fn accepts(&self, value: &str) -> bool {
+        self.rules.iter().all(|rule| rule.accepts(value))
+    }
+}
+
+impl Schema {
+    /// Parses a schema written as TOML, as in `passport.toml`.
+    pub fn from_toml(toml: &str) -> Result<Self, SchemaError> {
+        let file: SchemaFile = toml::from_str(toml).map_err(SchemaError::Toml)?;
+        let mut fields: Vec<Field> = Vec::new();
+        for spec in file.fields {
+            if fields.iter().any(|f| f.name == spec.name) {
+                return Err(SchemaError::DuplicateField(spec.name));
+            }
+            let FieldSpec {
+                name,
+                required,
+                rules,
+            } = spec;
+            let rules = rules
+                .into_iter()
+                .map(|rule| rule.compile(&name))
+                .collect::<Result<_, _>>()?;
+            fields.push(Field {
+                name,
+                required,
+                rules,
+            });
+        }
+        Ok(Schema { fields })
+    }
+
+    /// Loads a schema from the TOML file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Schema::from_toml(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Whether `passport` has every required field, whatever their values.
+    pub fn has_required_fields(&self, passport: &Passport) -> bool {
+        self.fields
+            .iter()
+            .filter(|f| f.required)
+            .all(|f| passport.get(&f.name).is_some())
+    }
+
+    /// Whether `passport` has every required field and every field it has that the
+    /// schema names satisfies that field's rules.
+    pub fn is_valid(&self, passport: &Passport) -> bool {
+        self.fields.iter().all(|f| match passport.get(&f.name) {
+            Some(value) => f.accepts(value),
+            None => !f.required,
+        })
+    }
+}
+
+/// Why a schema couldn't be loaded.
+#[derive(Debug)]
+pub enum SchemaError {
+    Toml(toml::de::Error),
+    Regex { field: String, err: regex::Error },
+    DuplicateField(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Toml(err) => write!(f, "{}", err),
+            SchemaError::Regex { field, err } => write!(f, "field `{}`: {}", field, err),
+            SchemaError::DuplicateField(field) => write!(f, "field `{}` is listed twice", field),
+        }
+    }
+}
+
+impl error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SchemaError::Toml(err) => Some(err),
+            SchemaError::Regex { err, .. } => Some(err),
+            SchemaError::DuplicateField(_) => None,
+        }
+    }
+}
+
+/// A schema as written, before its regexes are compiled.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    #[serde(default, rename = "field")]
+    fields: Vec<FieldSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldSpec {
+    name: String,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum RuleSpec {
+    Int(Limits),
+    Regex { pattern: String },
+    Enum { values: Vec<String> },
+    Unit { units: BTreeMap<String, Limits> },
+}
+
+impl RuleSpec {
+    fn compile(self, field: &str) -> Result<Rule, SchemaError> {
+        Ok(match self {
+            RuleSpec::Int(limits) => Rule::Int(limits),
+            RuleSpec::Regex { pattern } => {
+                let error = |err| SchemaError::Regex {
+                    field: field.to_owned(),
+                    err,
+                };
+                // Checking the pattern on its own first keeps one like `a)|(b` from
+                // escaping the anchors, and errors from quoting the wrapped version.
+                Regex::new(&pattern).map_err(error)?;
+                Rule::Regex(Regex::new(&format!("^(?:{})$", pattern)).map_err(error)?)
+            }
+            RuleSpec::Enum { values } => Rule::Enum(values),
+            RuleSpec::Unit { units } => Rule::Unit(units),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn passport(fields: &str) -> Passport {
+        let mut passport = Passport::default();
+        for part in fields.split_whitespace() {
+            let (key, val) = part.split_once(':').unwrap();
+            passport.set(key, val);
+        }
+        passport
+    }
+
+    #[test]
+    fn default_schema_test() {
+        let schema = default_schema();
+        assert_eq!(schema.fields().len(), 8);
+        let accepts = |name, value| schema.field(name).unwrap().accepts(value);
+
+        assert!(accepts("byr", "2002") && !accepts("byr", "2003"));
+        assert!(accepts("hgt", "60in") && accepts("hgt", "190cm"));
+        assert!(!accepts("hgt", "190in") && !accepts("hgt", "190") && !accepts("hgt", "cm"));
+        assert!(
+            accepts("hcl", "#123abc") && !accepts("hcl", "#123abz") && !accepts("hcl", "123abc")
+        );
+        assert!(accepts("ecl", "brn") && !accepts("ecl", "wat"));
+        assert!(accepts("pid", "000000001") && !accepts("pid", "0123456789"));
+        assert!(accepts("cid", "anything"));
+
+        let valid = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f";
+        assert!(schema.is_valid(&passport(valid)));
+        let incomplete = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 cid:1";
+        assert!(!schema.has_required_fields(&passport(incomplete)));
+        assert!(!schema.is_valid(&passport(incomplete)));
+    }
+
+    #[test]
+    fn from_toml_test() {
+        let schema = Schema::from_toml(
+            r#"
+            [[field]]
+            name = "age"
+            required = true
+            rules = [{ kind = "int", min = 0, max = 150 }, { kind = "regex", pattern = "[1-9][0-9]*" }]
+
+            [[field]]
+            name = "pid"
+            rules = [{ kind = "regex", pattern = "[0-9]{9}" }]
+
+            [[field]]
+            name = "eye"
+            rules = [{ kind = "enum", values = ["blue", "brown"] }]
+            "#,
+        )
+        .unwrap();
+        assert!(schema.is_valid(&passport("age:42 other:x")));
+        assert!(!schema.is_valid(&passport("age:042")));
+        assert!(!schema.is_valid(&passport("age:42 eye:red")));
+        // Patterns match the whole value, not just part of it.
+        assert!(schema.is_valid(&passport("age:42 pid:012345678")));
+        assert!(!schema.is_valid(&passport("age:42 pid:0123456789")));
+        assert!(!schema.is_valid(&passport("age:42 pid:x012345678")));
+        assert!(!schema.has_required_fields(&passport("eye:blue")));
+        assert!(Schema::from_toml("").unwrap().is_valid(&passport("a:b")));
+
+        let err = Schema::from_toml("[[field]]\nname = \"a\"\n[[field]]\nname = \"a\"");
+        assert_eq!(err.unwrap_err().to_string(), "field `a` is listed twice");
+        let err = Schema::from_toml(
+            "[[field]]\nname = \"a\"\nrules = [{ kind = \"regex\", pattern = \"(\" }]",
+        );
+        assert!(matches!(err, Err(SchemaError::Regex { field, .. }) if field == "a"));
+        let err = Schema::from_toml(
+            "[[field]]\nname = \"a\"\nrules = [{ kind = \"regex\", pattern = \"a)|(b\" }]",
+        );
+        assert!(matches!(err, Err(SchemaError::Regex { field, .. }) if field == "a"));
+        let err = Schema::from_toml("[[field]]\nname = \"a\"\nrules = [{ kind = \"date\" }]");
+        assert!(matches!(err, Err(SchemaError::Toml(_))));
+    }
+}
@@ -1,57 +1,97 @@
+use crate::input::{lines, Line};
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> Vec<Vec<Direction>> {
-    input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| match c {
-                    'F' => Direction::FRONT,
-                    'B' => Direction::BACK,
-                    'L' => Direction::LEFT,
-                    'R' => Direction::RIGHT,
-                    _ => panic!("Invalid character: {}", c),
-                })
-                .collect()
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
+    let passes = lines(5, input)
+        .map(parse_boarding_pass)
+        .collect::<Result<Vec<_>, _>>()?;
+    if passes.is_empty() {
+        return Err(ParseError::empty(5));
+    }
+    Ok(passes)
+}
+
+fn parse_boarding_pass(line: Line) -> Result<Vec<Direction>, ParseError> {
+    let mut steps = Vec::with_capacity(10);
+    for (i, (at, c)) in line.text.char_indices().enumerate() {
+        let step = match (i, c) {
+            (0..=6, 'F') => Direction::FRONT,
+            (0..=6, 'B') => Direction::BACK,
+            (7..=9, 'L') => Direction::LEFT,
+            (7..=9, 'R') => Direction::RIGHT,
+            (0..=6, _) => return Err(line.error(&line.text[at..], "expected `F` or `B`")),
+            (7..=9, _) => return Err(line.error(&line.text[at..], "expected `L` or `R`")),
+            _ => break,
+        };
+        steps.push(step);
+    }
+    if steps.len() != 10 || line.text.len() != 10 {
+        return Err(line.error(line.text, "expected exactly 10 characters"));
+    }
+    Ok(steps)
 }
 
 #[aoc(day5, part1)]
-pub fn solve_part1(input: &[Vec<Direction>]) -> u16 {
-    input.iter().map(|x| get_seat_id(x)).max().unwrap()
+pub fn solve_part1(input: &[Vec<Direction>]) -> Option<u16> {
+    input.iter().map(|x| get_seat_id(x)).max()
 }
 
 #[aoc(day5, part2)]
-pub fn solve_part2(input: &[Vec<Direction>]) -> u16 {
+pub fn solve_part2(input: &[Vec<Direction>]) -> Option<u16> {
     let mut seat_ids: Vec<u16> = input.iter().map(|x| get_seat_id(x)).collect();
     seat_ids.sort_unstable();
 
     let mut result = None;
-    let mut previous = seat_ids[0];
+    let mut previous = *seat_ids.first()?;
     let mut it = seat_ids.iter().skip(1).peekable();
     while let Some(sid) = it.next() {
         if let Some(&&next_sid) = it.peek() {
-            if !(previous == sid - 1 && next_sid == sid + 1) {
+            if !(previous + 1 == *sid && next_sid == sid + 1) {
                 result = Some(sid + 1);
                 break;
             }
             previous = *sid;
         }
     }
-    result.unwrap()
+    result
 }
 
-fn get_seat_id(steps: &[Direction]) -> u16 {
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<Vec<Direction>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+}
+
+pub fn get_seat_id(steps: &[Direction]) -> u16 {
     (get_row(steps) * 8) + get_seat(steps)
 }
 
-fn get_row(remaining: &[Direction]) -> u16 {
+pub fn get_row(remaining: &[Direction]) -> u16 {
     binary_partition(0, 127, &remaining[0..7])
 }
 
-fn get_seat(remaining: &[Direction]) -> u16 {
+pub fn get_seat(remaining: &[Direction]) -> u16 {
     binary_partition(0, 7, &remaining[7..])
 }
 
@@ -67,7 +107,8 @@ fn binary_partition(low: u16, high: u16, steps: &[Direction]) -> u16 {
     }
 }
 
-#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Direction {
     FRONT,
     BACK,
@@ -96,4 +137,29 @@ mod test {
         ];
         assert_eq!(get_seat(&steps), 5);
     }
+
+    #[test]
+    fn solve_test() {
+        let seats = input_generator("FFFFFFFLLL\nFFFFFFFLRR\nFFFFFFFLLR").unwrap();
+        assert_eq!(solve_part1(&seats), Some(3));
+        assert_eq!(solve_part2(&seats), Some(2));
+
+        let seat = input_generator("BFFFBBFRRR").unwrap();
+        assert_eq!(solve_part2(&seat), None);
+        assert_eq!(solve_part2(&[]), None);
+    }
+
+    #[test]
+    fn input_generator_test() {
+        assert_eq!(get_seat_id(&input_generator("BFFFBBFRRR").unwrap()[0]), 567);
+
+        let err = input_generator("BFFFBBFRRR\nBFFFBBRRRR").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 7, "RRRR"));
+
+        let err = input_generator("BFFFBBFRR").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        assert!(input_generator("BFFFBBFRRRR").is_err());
+        assert_eq!(input_generator("").unwrap_err(), ParseError::empty(5));
+    }
 }
@@ -1,11 +1,26 @@
+use crate::input::groups;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 
 #[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Vec<Vec<String>> {
-    input
-        .split("\n\n")
-        .map(|group_answers| group_answers.lines().map(|str| str.to_string()).collect())
+pub fn input_generator(input: &str) -> Result<Vec<Vec<String>>, ParseError> {
+    groups(6, input)
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .map(
+                    |line| match line.text.find(|c: char| !c.is_ascii_lowercase()) {
+                        Some(at) => {
+                            Err(line.error(&line.text[at..], "expected answers `a` to `z`"))
+                        }
+                        None => Ok(line.text.to_string()),
+                    },
+                )
+                .collect()
+        })
         .collect()
 }
 
@@ -33,11 +48,51 @@ pub fn solve_part2(input: &[Vec<String>]) -> usize {
                 .map(|row| row.chars().collect::<HashSet<_>>())
                 .collect::<Vec<_>>();
 
-            let mut in_all = answers[0].clone();
-            for a in &answers[1..] {
+            let (first, rest) = match answers.split_first() {
+                Some(split) => split,
+                None => return 0,
+            };
+            let mut in_all = first.clone();
+            for a in rest {
                 in_all.retain(|ch| a.contains(ch));
             }
             in_all.len()
         })
         .sum()
 }
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Vec<Vec<String>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn input_generator_test() {
+        let groups = input_generator("abc\n\na\nb\n\n\nab\nac\n").unwrap();
+        assert_eq!(solve_part1(&groups), 8);
+        assert_eq!(solve_part2(&groups), 4);
+        assert_eq!(solve_part2(&[vec![]]), 0);
+
+        let err = input_generator("abc\n\na\nB").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (4, 1, "B"));
+    }
+}
@@ -1,56 +1,89 @@
-use std::collections::{HashMap, VecDeque};
+use crate::input::{lines, Line};
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::collections::{HashMap, VecDeque};
 
 const GOAL: &str = "shiny gold";
 
+/// Maps each bag colour to the bags (with counts) it must directly contain.
+pub type Rules = HashMap<String, Vec<(usize, String)>>;
+
 lazy_static! {
-    static ref LINE_RE: Regex = Regex::new(r"(\w+ \w+) bags contain (.*)").unwrap();
-    static ref ITEM_RE: Regex = Regex::new(r"(\d+) (\w+ \w+) bags?").unwrap();
+    static ref LINE_RE: Regex = Regex::new(r"^(\w+ \w+) bags contain (.*)\.$").unwrap();
+    static ref ITEM_RE: Regex = Regex::new(r"^(\d+) (\w+ \w+) bags?$").unwrap();
 }
 
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> HashMap<String, Vec<(usize, String)>> {
-    let mut bags = HashMap::<String, Vec<(usize, String)>>::new();
-    for line in input.lines() {
-        if let Some((item, items)) = LINE_RE
-            .captures(line.as_ref())
-            .and_then(|captures| Some((captures.get(1)?.as_str(), captures.get(2)?.as_str())))
-        {
-            bags.insert(
-                item.to_string(),
-                ITEM_RE
-                    .captures_iter(items)
-                    .filter_map(|captures| {
-                        Some((
-                            captures.get(1)?.as_str().parse().ok()?,
-                            captures.get(2)?.as_str().to_string(),
-                        ))
-                    }).collect(),
-            );
-        }
+pub fn input_generator(input: &str) -> Result<Rules, ParseError> {
+    let mut bags = Rules::new();
+    for line in lines(7, input) {
+        let captures = LINE_RE
+            .captures(line.text)
+            .ok_or_else(|| line.error(line.text, "expected `<colour> bags contain <contents>.`"))?;
+        let (item, items) = (&captures[1], captures.get(2).unwrap().as_str());
+        let contents = match items {
+            "no other bags" => Vec::new(),
+            _ => items
+                .split(", ")
+                .map(|part| parse_item(line, part))
+                .collect::<Result<_, _>>()?,
+        };
+        bags.insert(item.to_string(), contents);
     }
-    bags
+    Ok(bags)
+}
+
+fn parse_item(line: Line, part: &str) -> Result<(usize, String), ParseError> {
+    let captures = ITEM_RE
+        .captures(part)
+        .ok_or_else(|| line.error(part, "expected `<count> <colour> bag(s)`"))?;
+    let count = captures.get(1).unwrap().as_str();
+    Ok((line.parse(count, "a bag count")?, captures[2].to_string()))
 }
 
 #[aoc(day7, part1)]
-pub fn solve_part1(bags: &HashMap<String, Vec<(usize, String)>>) -> usize {
-    bags.keys().filter(|x| expand(&bags, x).any(|(_, item)| item == GOAL)).count()
+pub fn solve_part1(bags: &Rules) -> usize {
+    bags.keys()
+        .filter(|x| expand(bags, x).any(|(_, item)| item == GOAL))
+        .count()
 }
 
 #[aoc(day7, part2)]
-pub fn solve_part2(bags: &HashMap<String, Vec<(usize, String)>>) -> usize {
+pub fn solve_part2(bags: &Rules) -> usize {
     expand(bags, GOAL).map(|(count, _)| count).sum()
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Rules;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        Ok(solve_part2(input).into())
+    }
+}
+
+/// Breadth-first walk over every bag nested inside a bag, with multiplied counts.
 #[derive(Debug)]
-struct Expand<'a> {
-    bags: &'a HashMap<String, Vec<(usize, String)>>,
+pub struct Expand<'a> {
+    bags: &'a Rules,
     queue: VecDeque<(usize, &'a str)>,
 }
 
-fn expand<'a>(bags: &'a HashMap<String, Vec<(usize, String)>>, bag: &str) -> Expand<'a> {
+pub fn expand<'a>(bags: &'a Rules, bag: &str) -> Expand<'a> {
     Expand {
         bags,
         queue: bags.get(bag).map_or_else(VecDeque::new, |items| {
@@ -106,12 +139,34 @@ mod test {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(4, solve_part1(&input_generator(join(EXAMPLE_1, "\n").as_str())));
+        assert_eq!(
+            4,
+            solve_part1(&input_generator(join(EXAMPLE_1, "\n").as_str()).unwrap())
+        );
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(32, solve_part2(&input_generator(join(EXAMPLE_1, "\n").as_str())));
-        assert_eq!(126, solve_part2(&input_generator(join(EXAMPLE_2, "\n").as_str())));
+        assert_eq!(
+            32,
+            solve_part2(&input_generator(join(EXAMPLE_1, "\n").as_str()).unwrap())
+        );
+        assert_eq!(
+            126,
+            solve_part2(&input_generator(join(EXAMPLE_2, "\n").as_str()).unwrap())
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn input_generator_test() {
+        let err =
+            input_generator("light red bags contain 1 bright white bag, two muted yellow bags.")
+                .unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.text.as_str()),
+            (1, 44, "two muted yellow bags")
+        );
+
+        assert!(input_generator("light red bags hold 1 bright white bag.").is_err());
+    }
+}
@@ -1,32 +1,38 @@
+use crate::input::{lines, Line};
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::convert::TryFrom;
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
-        .map(|l| {
-            let operation = &l[..3];
-            let value = l[4..].parse().unwrap();
-            match operation {
-                "nop" => Instruction::NoOperation(value),
-                "acc" => Instruction::Accumulate(value),
-                "jmp" => Instruction::Jump(value),
-                _ => unreachable!(),
-            }
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    lines(8, input).map(parse_instruction).collect()
+}
+
+fn parse_instruction(line: Line) -> Result<Instruction, ParseError> {
+    let (operation, value) = line
+        .text
+        .split_once(' ')
+        .ok_or_else(|| line.error(line.text, "expected `<operation> <value>`"))?;
+    let value = line.parse(value.trim_start(), "a signed integer")?;
+    match operation {
+        "nop" => Ok(Instruction::NoOperation(value)),
+        "acc" => Ok(Instruction::Accumulate(value)),
+        "jmp" => Ok(Instruction::Jump(value)),
+        _ => Err(line.error(operation, "expected `nop`, `acc` or `jmp`")),
+    }
 }
 
 #[aoc(day8, part1)]
-pub fn solve_part1(instructions: &[Instruction]) -> isize {
+pub fn solve_part1(instructions: &[Instruction]) -> Option<isize> {
     match run_program(instructions) {
-        RunResult::InfiniteLoop(acc) => acc,
-        _ => unreachable!(),
+        RunResult::InfiniteLoop(acc) => Some(acc),
+        RunResult::Finished(_) | RunResult::OutOfBounds(_) | RunResult::Overflow => None,
     }
 }
 
 #[aoc(day8, part2)]
-pub fn solve_part2(instructions: &[Instruction]) -> isize {
+pub fn solve_part2(instructions: &[Instruction]) -> Option<isize> {
     let fix_locations: Vec<usize> = instructions
         .iter()
         .enumerate()
@@ -38,39 +44,69 @@ pub fn solve_part2(instructions: &[Instruction]) -> isize {
         let mut program = instructions.to_vec();
         program[fix].flip();
         match run_program(&program) {
-            RunResult::Finished(acc) => return acc,
-            RunResult::InfiniteLoop(_) => {}
+            RunResult::Finished(acc) => return Some(acc),
+            RunResult::InfiniteLoop(_) | RunResult::OutOfBounds(_) | RunResult::Overflow => {}
         }
     }
-    unreachable!()
+    None
 }
 
-#[derive(Clone)]
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Instruction {
     Jump(isize),
     Accumulate(isize),
     NoOperation(isize),
 }
 
-#[derive(Debug)]
-enum RunResult {
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RunResult {
     InfiniteLoop(isize),
     Finished(isize),
+    /// Jumped somewhere other than an instruction or just past the last one.
+    OutOfBounds(isize),
+    /// The accumulator or the instruction index overflowed.
+    Overflow,
 }
 
 impl Instruction {
-    fn execute(&self, acc: &mut isize, index: &mut isize) {
-        match self {
-            Instruction::Accumulate(value) => {
-                *acc += value;
-                *index += 1;
-            }
-            Instruction::Jump(value) => *index += value,
-            Instruction::NoOperation(_) => *index += 1,
-        }
+    /// Updates the accumulator and moves to the next instruction, or returns `None`,
+    /// leaving both unchanged, if either would overflow.
+    pub fn execute(&self, acc: &mut isize, index: &mut isize) -> Option<()> {
+        let (next_acc, next_index) = match self {
+            Instruction::Accumulate(value) => (acc.checked_add(*value)?, index.checked_add(1)?),
+            Instruction::Jump(value) => (*acc, index.checked_add(*value)?),
+            Instruction::NoOperation(_) => (*acc, index.checked_add(1)?),
+        };
+        *acc = next_acc;
+        *index = next_index;
+        Some(())
     }
 
-    fn flip(&mut self) {
+    pub fn flip(&mut self) {
         match self {
             Instruction::Jump(value) => *self = Instruction::NoOperation(*value),
             Instruction::NoOperation(value) => *self = Instruction::Jump(*value),
@@ -79,19 +115,22 @@ impl Instruction {
     }
 }
 
-fn run_program(program: &[Instruction]) -> RunResult {
+pub fn run_program(program: &[Instruction]) -> RunResult {
     let mut visited: Vec<bool> = vec![false; program.len()];
     let mut acc = 0;
     let mut index = 0;
     loop {
-        if index as usize == program.len() {
-            return RunResult::Finished(acc);
-        } else if visited[index as usize] {
-            return RunResult::InfiniteLoop(acc);
-        } else {
-            visited[index as usize] = true;
-            let instr = &program[index as usize];
-            instr.execute(&mut acc, &mut index);
+        match usize::try_from(index) {
+            Ok(i) if i == program.len() => return RunResult::Finished(acc),
+            Ok(i) if i > program.len() => return RunResult::OutOfBounds(acc),
+            Err(_) => return RunResult::OutOfBounds(acc),
+            Ok(i) if visited[i] => return RunResult::InfiniteLoop(acc),
+            Ok(i) => {
+                visited[i] = true;
+                if program[i].execute(&mut acc, &mut index).is_none() {
+                    return RunResult::Overflow;
+                }
+            }
         }
     }
 }
@@ -108,13 +147,46 @@ mod test {
 
     #[test]
     fn solve_part1_test() {
-        let input = input_generator(join(EXAMPLE_1, "\n").as_str());
-        assert_eq!(solve_part1(&input), 5);
+        let input = input_generator(join(EXAMPLE_1, "\n").as_str()).unwrap();
+        assert_eq!(solve_part1(&input), Some(5));
+
+        let input = input_generator("nop +0\nacc +1").unwrap();
+        assert_eq!(solve_part1(&input), None);
     }
 
     #[test]
     fn solve_part2_test() {
-        let input = input_generator(join(EXAMPLE_1, "\n").as_str());
-        assert_eq!(solve_part2(&input), 8);
+        let input = input_generator(join(EXAMPLE_1, "\n").as_str()).unwrap();
+        assert_eq!(solve_part2(&input), Some(8));
+
+        let input = input_generator("jmp +0\nacc +1\njmp -2").unwrap();
+        assert_eq!(solve_part2(&input), None);
+    }
+
+    #[test]
+    fn run_program_test() {
+        let input = input_generator("acc +2\njmp -2").unwrap();
+        assert_eq!(run_program(&input), RunResult::OutOfBounds(2));
+        let input = input_generator("acc +2\njmp +2").unwrap();
+        assert_eq!(run_program(&input), RunResult::OutOfBounds(2));
+
+        let input = input_generator("acc +9223372036854775807\nacc +1").unwrap();
+        assert_eq!(run_program(&input), RunResult::Overflow);
+        assert_eq!(solve_part1(&input), None);
+        assert_eq!(solve_part2(&input), None);
+        let input = input_generator("nop +0\njmp +9223372036854775807").unwrap();
+        assert_eq!(run_program(&input), RunResult::Overflow);
+    }
+
+    #[test]
+    fn input_generator_test() {
+        let err = input_generator("nop +0\nacc +1\njmp 4x").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 5, "4x"));
+
+        let err = input_generator("nop +0\nadd +1").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "add"));
+
+        assert!(input_generator("nop").is_err());
+        assert!(input_generator("é").is_err());
     }
 }
@@ -1,53 +1,77 @@
+use crate::input::lines;
+use crate::solution::Solution;
+use crate::{Answer, ParseError, Unsolved};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 use std::convert::identity;
 
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Vec<isize> {
-    input.lines().map(|l| l.parse().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<isize>, ParseError> {
+    lines(9, input)
+        .map(|l| l.parse(l.text, "a signed integer"))
+        .collect()
 }
 
 #[aoc(day9, part1)]
-pub fn solve_part1(input: &[isize]) -> isize {
+pub fn solve_part1(input: &[isize]) -> Option<isize> {
     get_first_invalid_number(input, 25)
 }
 
 #[aoc(day9, part2)]
-pub fn solve_part2(input: &[isize]) -> isize {
+pub fn solve_part2(input: &[isize]) -> Option<isize> {
     get_encryption_weakness(input, 25)
 }
 
-fn get_first_invalid_number(nums: &[isize], preamble_size: usize) -> isize {
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<isize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or(Unsolved::NoSolution)
+    }
+}
+
+/// The first number that is not the sum of two of the `preamble_size` numbers before it,
+/// or `None` if every number is.
+pub fn get_first_invalid_number(nums: &[isize], preamble_size: usize) -> Option<isize> {
     nums.windows(preamble_size + 1)
-        .filter_map(
-            |x| match has_two_sum(&x[..preamble_size], x[preamble_size]) {
-                false => Some(x[preamble_size]),
-                _ => None,
-            },
-        )
-        .collect::<Vec<isize>>()
-        .first()
-        .cloned()
-        .unwrap()
+        .find(|x| !has_two_sum(&x[..preamble_size], x[preamble_size]))
+        .map(|x| x[preamble_size])
 }
 
-fn get_encryption_weakness(nums: &[isize], preamble_size: usize) -> isize {
-    let first_invalid_number = get_first_invalid_number(nums, preamble_size);
+/// The sum of the smallest and largest numbers of a contiguous run adding up to the first
+/// invalid number, or `None` if there is no invalid number or no such run.
+pub fn get_encryption_weakness(nums: &[isize], preamble_size: usize) -> Option<isize> {
+    let first_invalid_number = get_first_invalid_number(nums, preamble_size)?;
 
     let contiguous_set: &[isize] = (2..nums.len())
         .map(|window_size| {
             nums.windows(window_size)
                 .find(|window| window.iter().sum::<isize>() == first_invalid_number)
         })
-        .find_map(identity)
-        .unwrap();
+        .find_map(identity)?;
 
-    let min = contiguous_set.iter().min().unwrap();
-    let max = contiguous_set.iter().max().unwrap();
-    min + max
+    let min = contiguous_set.iter().min()?;
+    let max = contiguous_set.iter().max()?;
+    Some(min + max)
 }
 
-fn has_two_sum(arr: &[isize], sum: isize) -> bool {
+pub fn has_two_sum(arr: &[isize], sum: isize) -> bool {
     let mut set = HashSet::new();
     for x in arr {
         if set.contains(&(sum - *x)) {
@@ -69,7 +93,8 @@ mod test {
             576,
         ];
 
-        assert_eq!(get_first_invalid_number(input.as_slice(), 5), 127);
+        assert_eq!(get_first_invalid_number(input.as_slice(), 5), Some(127));
+        assert_eq!(get_first_invalid_number(&input[..6], 5), None);
     }
 
     #[test]
@@ -79,6 +104,7 @@ mod test {
             576,
         ];
 
-        assert_eq!(get_encryption_weakness(input.as_slice(), 5), 62);
+        assert_eq!(get_encryption_weakness(input.as_slice(), 5), Some(62));
+        assert_eq!(get_encryption_weakness(&[1, 2, 3, 4, 100], 3), None);
     }
 }
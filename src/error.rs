@@ -0,0 +1,114 @@
+use std::error;
+use std::fmt;
+
+/// Describes why a day's puzzle input could not be turned into its domain types.
+///
+/// `line` and `column` are 1-based, and `text` holds the offending slice of the input.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(
+        day: u8,
+        line: usize,
+        column: usize,
+        text: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            text: text.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Error for input that has no content at all.
+    pub fn empty(day: u8) -> Self {
+        ParseError::new(day, 1, 1, "", "input is empty")
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {} (found `{}`)",
+            self.day, self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Why a day's solver gave no answer for an input it parsed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Unsolved {
+    /// The input has no answer.
+    NoSolution,
+    /// The answer doesn't fit in the type it's computed in.
+    Overflow,
+}
+
+impl fmt::Display for Unsolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unsolved::NoSolution => write!(f, "the input has no solution"),
+            Unsolved::Overflow => write!(f, "the answer overflows"),
+        }
+    }
+}
+
+impl error::Error for Unsolved {}
+
+/// Everything that can go wrong when asking the crate to solve a puzzle.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    Parse(ParseError),
+    UnknownDay(u8),
+    UnknownPart(u8),
+    NoSolution { day: u8, part: u8 },
+    Overflow { day: u8, part: u8 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::UnknownDay(day) => write!(f, "day {} is not implemented", day),
+            Error::UnknownPart(part) => write!(f, "part {} does not exist", part),
+            Error::NoSolution { day, part } => {
+                write!(
+                    f,
+                    "day {} part {} has no solution for this input",
+                    day, part
+                )
+            }
+            Error::Overflow { day, part } => {
+                write!(f, "day {} part {} overflows for this input", day, part)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
@@ -0,0 +1,338 @@
+use crate::input::lines;
+use crate::ParseError;
+use std::fmt;
+use std::iter::successors;
+use std::ops::{Index, IndexMut};
+
+/// Offsets to the orthogonally adjacent cells.
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to the orthogonally and diagonally adjacent cells.
+pub const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid of cells stored row by row, addressed as `(x, y)` with `x` the
+/// column and `y` the row, both counting from the top left.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from row-major cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there aren't exactly `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "grid must be width * height cells"
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a grid from rows, which must all have the same length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "all rows must have the same length"
+        );
+        let height = rows.len();
+        Grid::new(width, height, rows.into_iter().flatten().collect())
+    }
+
+    /// Parses a character map, one row per line, turning each character into a cell
+    /// with `cell`. `expected` describes the accepted characters for error messages.
+    pub fn parse<F>(day: u8, input: &str, expected: &str, cell: F) -> Result<Self, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for line in lines(day, input) {
+            let row = line
+                .text
+                .char_indices()
+                .map(|(i, c)| {
+                    cell(c).ok_or_else(|| {
+                        line.error(&line.text[i..], format!("expected {}", expected))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(line.error(
+                        line.text,
+                        format!("expected a row of width {}", first.len()),
+                    ));
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Grid::from_rows(rows))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// The cell at `(x, y)`, or `None` outside the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        if self.contains(x, y) {
+            Some(&self.cells[y as usize * self.width + x as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[y as usize * self.width + x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// The cell at `(x, y)` with the grid repeating endlessly in every direction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid is empty.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize);
+        let y = y.rem_euclid(self.height as isize);
+        &self[(x as usize, y as usize)]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self[(x, y)] = value;
+    }
+
+    /// In-bounds positions at the given offsets from `(x, y)`.
+    fn around<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            if self.contains(nx, ny) {
+                Some((nx as usize, ny as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Positions orthogonally adjacent to `(x, y)` that lie inside the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.around(x, y, &NEIGHBOURS4)
+    }
+
+    /// Positions orthogonally or diagonally adjacent to `(x, y)` that lie inside the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.around(x, y, &NEIGHBOURS8)
+    }
+
+    /// Positions reached by repeatedly stepping `(dx, dy)` from `(x, y)`, excluding the
+    /// start, until the ray leaves the grid. A zero step yields nothing.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let first = if dx == 0 && dy == 0 {
+            None
+        } else {
+            Some((x as isize + dx, y as isize + dy))
+        };
+        successors(first, move |&(x, y)| Some((x + dx, y + dy)))
+            .take_while(move |&(x, y)| self.contains(x, y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// A grid of the same shape with `f` applied to every position and cell.
+    pub fn map<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut((usize, usize), &T) -> U,
+    {
+        let cells = self
+            .positions()
+            .zip(self.iter())
+            .map(|(pos, cell)| f(pos, cell));
+        Grid::new(self.width, self.height, cells.collect())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows().take(self.height) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse(0, "123\n456", "a digit", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let err = Grid::parse(0, "123\n4x6", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.reason.as_str()),
+            (2, 2, "expected a digit")
+        );
+
+        let err = Grid::parse(0, "123\n45", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        assert!(Grid::parse(0, "", "a digit", |c| c.to_digit(10))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn accessors_test() {
+        let grid = digits();
+        assert_eq!(grid.get(2, 1), Some(&6));
+        assert_eq!(grid.get(3, 1), None);
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get_wrapping(3, 1), &4);
+        assert_eq!(grid.get_wrapping(-1, -1), &6);
+        assert_eq!(grid[(1, 0)], 2);
+    }
+
+    #[test]
+    fn neighbours_test() {
+        let grid = digits();
+        let values = |it: &mut dyn Iterator<Item = (usize, usize)>| {
+            let mut v: Vec<u32> = it.map(|p| grid[p]).collect();
+            v.sort_unstable();
+            v
+        };
+        assert_eq!(values(&mut grid.neighbours4(0, 0)), vec![2, 4]);
+        assert_eq!(values(&mut grid.neighbours8(1, 0)), vec![1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn ray_test() {
+        let grid = digits();
+        assert_eq!(
+            grid.ray(0, 0, 1, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0)]
+        );
+        assert_eq!(grid.ray(0, 0, 1, 1).collect::<Vec<_>>(), vec![(1, 1)]);
+        assert_eq!(grid.ray(0, 0, -1, 0).count(), 0);
+        assert_eq!(grid.ray(0, 0, 0, 0).count(), 0);
+    }
+
+    #[test]
+    fn rows_and_columns_test() {
+        let grid = digits();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3], &[4, 5, 6]]
+        );
+        let columns: Vec<Vec<u32>> = grid.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(grid.map(|(x, y), &n| n * 10 + (x + y) as u32)[(2, 1)], 63);
+    }
+}
@@ -0,0 +1,136 @@
+//! Normalization shared by every input generator: `\n` and `\r\n` line endings,
+//! surrounding whitespace on each line, and blank lines are all handled here so
+//! the generators only see meaningful text.
+
+use crate::ParseError;
+use std::str::FromStr;
+
+/// One trimmed, non-blank line of puzzle input along with where it came from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Line<'a> {
+    pub day: u8,
+    pub line: usize,
+    pub text: &'a str,
+    indent: usize,
+}
+
+impl<'a> Line<'a> {
+    fn new(day: u8, index: usize, raw: &'a str) -> Self {
+        let text = raw.trim();
+        let indent = raw[..raw.len() - raw.trim_start().len()].chars().count();
+        Line {
+            day,
+            line: index + 1,
+            text,
+            indent,
+        }
+    }
+
+    /// Error pointing at `part`, which should be a slice of this line's text.
+    pub fn error(&self, part: &str, reason: impl Into<String>) -> ParseError {
+        ParseError::new(self.day, self.line, self.column(part), part, reason)
+    }
+
+    /// Parses `part` (a slice of this line's text), describing it as `what` on failure.
+    pub fn parse<T: FromStr>(&self, part: &str, what: &str) -> Result<T, ParseError> {
+        part.parse()
+            .map_err(|_| self.error(part, format!("expected {}", what)))
+    }
+
+    /// Column of `part` in the original line, counting from 1.
+    fn column(&self, part: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize).wrapping_sub(start);
+        if offset > self.text.len() || !self.text.is_char_boundary(offset) {
+            return self.indent + 1;
+        }
+        self.indent + self.text[..offset].chars().count() + 1
+    }
+}
+
+/// Every line of `input`, blank ones included, so callers can see group boundaries.
+fn all_lines(day: u8, input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .split('\n')
+        .enumerate()
+        .map(move |(i, raw)| Line::new(day, i, raw))
+}
+
+/// The non-blank lines of `input`, trimmed and numbered from 1.
+pub(crate) fn lines(day: u8, input: &str) -> impl Iterator<Item = Line<'_>> {
+    all_lines(day, input).filter(|l| !l.text.is_empty())
+}
+
+/// Groups of consecutive non-blank lines, separated by one or more blank lines.
+pub(crate) fn groups(day: u8, input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    for line in all_lines(day, input) {
+        if !line.text.is_empty() {
+            current.push(line);
+        } else if !current.is_empty() {
+            groups.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// Rewrites `input` with `\n` line endings, trimmed lines and no leading or trailing
+/// blank lines, so the same puzzle input always has the same text.
+pub fn normalize(input: &str) -> String {
+    let lines: Vec<&str> = input.split('\n').map(str::trim).collect();
+    let first = lines.iter().position(|l| !l.is_empty());
+    let last = lines.iter().rposition(|l| !l.is_empty());
+    match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last].join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lines_test() {
+        let input = "  1721 \r\n\r\n979\r\n\n";
+        let lines: Vec<_> = lines(1, input).map(|l| (l.line, l.text)).collect();
+        assert_eq!(lines, vec![(1, "1721"), (3, "979")]);
+    }
+
+    #[test]
+    fn groups_test() {
+        let input = "\nabc\r\n\r\n\r\na\nb\n  \nc\n";
+        let groups: Vec<Vec<_>> = groups(6, input)
+            .iter()
+            .map(|g| g.iter().map(|l| (l.line, l.text)).collect())
+            .collect();
+        assert_eq!(
+            groups,
+            vec![vec![(2, "abc")], vec![(5, "a"), (6, "b")], vec![(8, "c")]]
+        );
+    }
+
+    #[test]
+    fn error_test() {
+        let line = lines(8, "nop +0\r\n  jmp +x").nth(1).unwrap();
+        let err = line.parse::<isize>(&line.text[4..], "a signed integer");
+        assert_eq!(
+            err,
+            Err(ParseError::new(8, 2, 7, "+x", "expected a signed integer"))
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "day 8, line 2, column 7: expected a signed integer (found `+x`)"
+        );
+    }
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(normalize("\r\n939 \r\n7,13,x\r\n\r\n"), "939\n7,13,x");
+        assert_eq!(normalize(" \n\n"), "");
+    }
+}
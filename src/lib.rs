@@ -1,19 +1,111 @@
 use aoc_runner_derive::aoc_lib;
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+mod answer;
+pub mod answers;
+mod error;
+pub mod grid;
+pub mod input;
+pub mod registry;
+mod solution;
+
+pub use answer::Answer;
+pub use error::{Error, ParseError, Unsolved};
+pub use solution::{Day, Parsed, Solution};
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+/// Parses `input` for `day` and solves the requested `part` (1 or 2).
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, Error> {
+    registry::find(day)
+        .ok_or(Error::UnknownDay(day))?
+        .solve(part, input)
+}
 
 aoc_lib! { year = 2020 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn solve_test() {
+        assert_eq!(solve(15, 1, "0,3,6"), Ok(Answer::Unsigned(436)));
+        assert_eq!(
+            solve(
+                8,
+                2,
+                "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6"
+            ),
+            Ok(Answer::Signed(8))
+        );
+        assert_eq!(solve(16, 1, ""), Err(Error::UnknownDay(16)));
+        assert_eq!(solve(1, 3, "1721"), Err(Error::UnknownPart(3)));
+        assert!(matches!(solve(1, 1, "x"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn solve_reports_no_solution_test() {
+        assert_eq!(
+            solve(9, 1, "1\n2\n3"),
+            Err(Error::NoSolution { day: 9, part: 1 })
+        );
+        assert_eq!(
+            solve(9, 2, "1\n2\n3"),
+            Err(Error::NoSolution { day: 9, part: 2 })
+        );
+        assert_eq!(solve(5, 1, "BFFFBBFRRR"), Ok(Answer::Unsigned(567)));
+        assert_eq!(
+            solve(5, 2, "BFFFBBFRRR"),
+            Err(Error::NoSolution { day: 5, part: 2 })
+        );
+        assert_eq!(
+            solve(8, 1, "nop +0\nacc +1"),
+            Err(Error::NoSolution { day: 8, part: 1 })
+        );
+        assert_eq!(
+            solve(13, 2, "939\n2,4"),
+            Err(Error::NoSolution { day: 13, part: 2 })
+        );
+        assert_eq!(
+            solve(1, 1, "4611686018427387904\n-4611686018427385884"),
+            Err(Error::Overflow { day: 1, part: 1 })
+        );
+    }
+
+    #[test]
+    fn solve_normalizes_input_test() {
+        assert_eq!(solve(15, 1, "0, 3, 6\r\n\r\n"), Ok(Answer::Unsigned(436)));
+        assert_eq!(
+            solve(6, 1, "\r\nabc\r\n\r\na\r\nb\r\nc \r\n\r\n\r\nab\r\n"),
+            Ok(Answer::Unsigned(8))
+        );
+        assert_eq!(
+            solve(13, 2, "939\r\n7,13,x,x,59,x,31,19\r\n"),
+            Ok(Answer::Unsigned(1068781))
+        );
+    }
+
+    proptest! {
+        // The fuzz targets under `fuzz/` explore this properly; this keeps a cheap
+        // version of the same guarantee in the regular test run.
+        #[test]
+        fn parse_never_panics(day in 1u8..=15, input in "[0-9a-z#.,:=+\\-\\[\\] \n]{0,64}") {
+            let _ = registry::find(day).unwrap().parse(&input);
+        }
+    }
+}
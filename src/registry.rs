@@ -0,0 +1,45 @@
+use crate::solution::Day;
+use crate::{day1, day10, day11, day12, day13, day14, day15, day2, day3, day4, day5, day6};
+use crate::{day7, day8, day9};
+
+static DAYS: [Day; 15] = [
+    Day::new::<day1::Day1>(),
+    Day::new::<day2::Day2>(),
+    Day::new::<day3::Day3>(),
+    Day::new::<day4::Day4>(),
+    Day::new::<day5::Day5>(),
+    Day::new::<day6::Day6>(),
+    Day::new::<day7::Day7>(),
+    Day::new::<day8::Day8>(),
+    Day::new::<day9::Day9>(),
+    Day::new::<day10::Day10>(),
+    Day::new::<day11::Day11>(),
+    Day::new::<day12::Day12>(),
+    Day::new::<day13::Day13>(),
+    Day::new::<day14::Day14>(),
+    Day::new::<day15::Day15>(),
+];
+
+/// Every implemented day, in order.
+pub fn days() -> &'static [Day] {
+    &DAYS
+}
+
+/// Looks up an implemented day by its number.
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day() == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn days_test() {
+        let numbers: Vec<u8> = days().iter().map(|d| d.day()).collect();
+        assert_eq!(numbers, (1..=15).collect::<Vec<u8>>());
+        assert_eq!(find(7).map(|d| d.day()), Some(7));
+        assert!(find(0).is_none());
+        assert!(find(16).is_none());
+    }
+}
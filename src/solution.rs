@@ -0,0 +1,90 @@
+use crate::{Answer, Error, ParseError, Unsolved};
+
+/// A day's puzzle: how to parse its input and how to solve both parts.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Solves part 1, or says why the input has no answer.
+    fn part1(input: &Self::Input) -> Result<Answer, Unsolved>;
+
+    /// Solves part 2, or says why the input has no answer.
+    fn part2(input: &Self::Input) -> Result<Answer, Unsolved>;
+}
+
+/// Parsed input for some day, ready to be solved without knowing its concrete type.
+pub trait Parsed {
+    fn part1(&self) -> Result<Answer, Unsolved>;
+
+    fn part2(&self) -> Result<Answer, Unsolved>;
+}
+
+struct Input<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for Input<S> {
+    fn part1(&self) -> Result<Answer, Unsolved> {
+        S::part1(&self.0)
+    }
+
+    fn part2(&self) -> Result<Answer, Unsolved> {
+        S::part2(&self.0)
+    }
+}
+
+/// A registered day, with the [`Solution`] behind it erased so days can be iterated.
+#[derive(Clone, Copy)]
+pub struct Day {
+    day: u8,
+    parse: fn(&str) -> Result<Box<dyn Parsed>, ParseError>,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>() -> Self {
+        Day {
+            day: S::DAY,
+            parse: parse::<S>,
+        }
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        (self.parse)(input)
+    }
+
+    /// Solves `part` (1 or 2) of an already parsed input.
+    pub fn solve_parsed(&self, parsed: &dyn Parsed, part: u8) -> Result<Answer, Error> {
+        let answer = match part {
+            1 => parsed.part1(),
+            2 => parsed.part2(),
+            _ => return Err(Error::UnknownPart(part)),
+        };
+        answer.map_err(|unsolved| match unsolved {
+            Unsolved::NoSolution => Error::NoSolution {
+                day: self.day,
+                part,
+            },
+            Unsolved::Overflow => Error::Overflow {
+                day: self.day,
+                part,
+            },
+        })
+    }
+
+    /// Parses `input` and solves `part` (1 or 2).
+    pub fn solve(&self, part: u8, input: &str) -> Result<Answer, Error> {
+        if part != 1 && part != 2 {
+            return Err(Error::UnknownPart(part));
+        }
+        self.solve_parsed(self.parse(input)?.as_ref(), part)
+    }
+}
+
+fn parse<S: Solution + 'static>(input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+    Ok(Box::new(Input::<S>(S::parse(input)?)))
+}